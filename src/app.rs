@@ -6,7 +6,10 @@ use freya::{
 use freya_router::prelude::Router;
 
 use crate::layouts::{LoginLayout, MainLayout, MapLayout, RootLayout};
-use crate::pages::{Info, Loading, Login, Map, MinimapSettingsPage, ServerSelect, Shops, Team};
+use crate::pages::{
+    AnnotationsPage, GeofencesPage, Info, Loading, Login, Map, MinimapSettingsPage,
+    NotificationSettingsPage, ServerSelect, Shops, Team, Timeline,
+};
 use crate::{Data, DataChannel};
 
 pub struct MyApp {
@@ -42,10 +45,18 @@ pub enum Route {
                     Map,
                     #[route("/minimap_settings")]
                     MinimapSettingsPage,
+                    #[route("/geofences")]
+                    GeofencesPage,
+                    #[route("/annotations")]
+                    AnnotationsPage,
                 #[end_layout]
             #[end_nest]
         #[route("/team")]
         Team,
         #[route("/shops")]
         Shops,
+        #[route("/timeline")]
+        Timeline,
+        #[route("/notifications")]
+        NotificationSettingsPage,
 }
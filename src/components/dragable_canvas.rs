@@ -3,8 +3,35 @@ use freya::prelude::*;
 pub const MIN_ZOOM: f32 = 0.3;
 pub const MAX_ZOOM: f32 = 1.6;
 
+/// Pointer travel in pixels below which a press and release counts as a click rather than a drag.
+const CLICK_TOLERANCE: f32 = 4.0;
+
 pub type Point2D = euclid::Point2D<f32, ()>;
 
+/// Pointer drag in the coordinates of the canvas children, emitted instead of panning.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DrawEvent {
+    Start(Point2D),
+    Move(Point2D),
+    End(Point2D),
+}
+
+impl DrawEvent {
+    pub fn point(&self) -> Point2D {
+        match *self {
+            DrawEvent::Start(point) | DrawEvent::Move(point) | DrawEvent::End(point) => point,
+        }
+    }
+
+    pub fn map(self, f: impl FnOnce(Point2D) -> Point2D) -> Self {
+        match self {
+            DrawEvent::Start(point) => DrawEvent::Start(f(point)),
+            DrawEvent::Move(point) => DrawEvent::Move(f(point)),
+            DrawEvent::End(point) => DrawEvent::End(f(point)),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct DragableCanvas {
     elements: Vec<Element>,
@@ -13,6 +40,11 @@ pub struct DragableCanvas {
     zoom: Option<Writable<f32>>,
     children_size: Option<Readable<Point2D>>,
     interactable: Option<Readable<bool>>,
+    initial_pos: Option<Point2D>,
+    on_draw: Option<EventHandler<DrawEvent>>,
+    on_click: Option<EventHandler<Point2D>>,
+    on_hover: Option<EventHandler<Option<Point2D>>>,
+    on_secondary_click: Option<EventHandler<(Point2D, Point2D)>>,
 }
 
 #[allow(dead_code)]
@@ -25,6 +57,11 @@ impl DragableCanvas {
             zoom: None,
             children_size: None,
             interactable: None,
+            initial_pos: None,
+            on_draw: None,
+            on_click: None,
+            on_hover: None,
+            on_secondary_click: None,
         }
     }
 
@@ -47,6 +84,41 @@ impl DragableCanvas {
         self.interactable = Some(interactable.into());
         self
     }
+
+    /// Position to use instead of the center once the canvas is first sized.
+    pub fn initial_pos(mut self, initial_pos: Option<Point2D>) -> Self {
+        self.initial_pos = initial_pos;
+        self
+    }
+
+    /// While set, left dragging reports [`DrawEvent`]s instead of moving the canvas.
+    pub fn on_draw(mut self, on_draw: Option<EventHandler<DrawEvent>>) -> Self {
+        self.on_draw = on_draw;
+        self
+    }
+
+    /// Reports left clicks in the coordinates of the canvas children, dragging still pans.
+    pub fn on_click(mut self, on_click: Option<EventHandler<Point2D>>) -> Self {
+        self.on_click = on_click;
+        self
+    }
+
+    /// Reports the pointer in the coordinates of the canvas children while it is not dragging,
+    /// `None` once it leaves the canvas.
+    pub fn on_hover(mut self, on_hover: Option<EventHandler<Option<Point2D>>>) -> Self {
+        self.on_hover = on_hover;
+        self
+    }
+
+    /// Reports right clicks in the coordinates of the canvas children, along with the global
+    /// pointer location.
+    pub fn on_secondary_click(
+        mut self,
+        on_secondary_click: Option<EventHandler<(Point2D, Point2D)>>,
+    ) -> Self {
+        self.on_secondary_click = on_secondary_click;
+        self
+    }
 }
 
 impl ChildrenExt for DragableCanvas {
@@ -58,6 +130,8 @@ impl ChildrenExt for DragableCanvas {
 impl Component for DragableCanvas {
     fn render(&self) -> impl IntoElement {
         let mut dragging = use_state(|| false);
+        let mut press_location: State<Option<Point2D>> = use_state(|| None);
+        let mut drawing = use_state(|| false);
         let mut hover = use_state(|| false);
         let mut mouse_coords_global: State<Point2D> = use_state(|| Point2D::new(0.0, 0.0));
         let mut mouse_coords_local: State<Point2D> = use_state(|| Point2D::new(0.0, 0.0));
@@ -87,11 +161,32 @@ impl Component for DragableCanvas {
 
         let mut once = use_state(|| false);
 
+        let to_children = {
+            let pos = pos.clone();
+            let zoom = zoom.clone();
+            let children_size = children_size.clone();
+            move |local: Point2D| {
+                let zoom = *zoom.read();
+                let children_size = *children_size.read();
+                let offset = local
+                    - *pos.read()
+                    - (size.read().size / 2.0).into()
+                    - ((children_size - (children_size * zoom)) / 2.0);
+                Point2D::new(offset.x / zoom, offset.y / zoom)
+            }
+        };
+        let drag_cursor = if self.on_draw.is_some() || self.on_click.is_some() {
+            CursorIcon::Crosshair
+        } else {
+            CursorIcon::Grab
+        };
+
         rect()
             //Center map
             .on_sized({
                 let children_size = children_size.clone();
                 let mut pos = pos.clone();
+                let initial_pos = self.initial_pos;
                 move |e: Event<SizedEventData>| {
                     *size.write() = e.area;
                     if once() {
@@ -102,43 +197,107 @@ impl Component for DragableCanvas {
                     }
                     *once.write() = true;
 
-                    *pos.write() = Point2D::new(children_size.read().x / -2.0, children_size.read().y / -2.0);
+                    *pos.write() = initial_pos.unwrap_or_else(|| {
+                        Point2D::new(children_size.read().x / -2.0, children_size.read().y / -2.0)
+                    });
                 }
             })
             .overflow(Overflow::Clip)
             .width(Size::Fill)
             .height(Size::Fill)
             .maybe(*interactable.read(), |rect| {
-                rect.on_mouse_down(move |e: Event<MouseEventData>| {
-                    if e.button != Some(MouseButton::Left) {
-                        return;
+                rect.on_mouse_down({
+                    let on_draw = self.on_draw.clone();
+                    let on_secondary_click = self.on_secondary_click.clone();
+                    let to_children = to_children.clone();
+                    move |e: Event<MouseEventData>| {
+                        if e.button == Some(MouseButton::Right) {
+                            if let Some(on_secondary_click) = &on_secondary_click {
+                                on_secondary_click.call((
+                                    to_children(e.element_location.to_f32()),
+                                    e.global_location.to_f32(),
+                                ));
+                            }
+                            return;
+                        }
+                        if e.button != Some(MouseButton::Left) {
+                            return;
+                        }
+                        if let Some(on_draw) = &on_draw {
+                            *drawing.write() = true;
+                            on_draw.call(DrawEvent::Start(to_children(
+                                e.element_location.to_f32(),
+                            )));
+                            return;
+                        }
+                        *dragging.write() = true;
+                        *press_location.write() = Some(e.global_location.to_f32());
+                        *mouse_coords_global.write() = e.global_location.to_f32();
+                        Cursor::set(CursorIcon::Grabbing);
                     }
-                    *dragging.write() = true;
-                    *mouse_coords_global.write() = e.global_location.to_f32();
-                    Cursor::set(CursorIcon::Grabbing);
                 })
-                .on_global_mouse_up(move |e: Event<MouseEventData>| {
-                    if e.button != Some(MouseButton::Left) {
-                        return;
-                    }
-                    *dragging.write() = false;
-                    if hover() {
-                        Cursor::set(CursorIcon::Grab);
-                    } else {
-                        Cursor::set(CursorIcon::default());
+                .on_global_mouse_up({
+                    let on_draw = self.on_draw.clone();
+                    let on_click = self.on_click.clone();
+                    let to_children = to_children.clone();
+                    move |e: Event<MouseEventData>| {
+                        if e.button != Some(MouseButton::Left) {
+                            return;
+                        }
+                        if drawing() {
+                            *drawing.write() = false;
+                            if let Some(on_draw) = &on_draw {
+                                on_draw.call(DrawEvent::End(to_children(mouse_coords_local())));
+                            }
+                            return;
+                        }
+                        *dragging.write() = false;
+                        let press = press_location.write().take();
+                        if let Some(start) = press
+                            && let Some(on_click) = &on_click
+                            && (e.global_location.to_f32() - start).length() < CLICK_TOLERANCE
+                        {
+                            on_click.call(to_children(mouse_coords_local()));
+                        }
+                        if hover() {
+                            Cursor::set(drag_cursor);
+                        } else {
+                            Cursor::set(CursorIcon::default());
+                        }
                     }
                 })
                 .on_pointer_enter(move |_| {
                     *hover.write() = true;
-                    Cursor::set(CursorIcon::Grab);
+                    Cursor::set(drag_cursor);
                 })
-                .on_pointer_leave(move |_| {
-                    *hover.write() = false;
-                    Cursor::set(CursorIcon::default());
+                .on_pointer_leave({
+                    let on_hover = self.on_hover.clone();
+                    move |_| {
+                        *hover.write() = false;
+                        Cursor::set(CursorIcon::default());
+                        if let Some(on_hover) = &on_hover {
+                            on_hover.call(None);
+                        }
+                    }
                 })
                 .on_mouse_move({
                     let mut pos = pos.clone();
+                    let on_draw = self.on_draw.clone();
+                    let on_hover = self.on_hover.clone();
                     move |e: Event<MouseEventData>| {
+                        if !drawing()
+                            && !dragging()
+                            && let Some(on_hover) = &on_hover
+                        {
+                            on_hover.call(Some(to_children(e.element_location.to_f32())));
+                        }
+                        if drawing()
+                            && let Some(on_draw) = &on_draw
+                        {
+                            on_draw.call(DrawEvent::Move(to_children(
+                                e.element_location.to_f32(),
+                            )));
+                        }
                         if dragging() {
                             let global_location = e.global_location.to_f32();
                             let new_pos = *pos.read() + (global_location - mouse_coords_global());
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use freya::{prelude::*, radio::use_radio};
+
+use crate::{
+    Data, DataChannel, colors,
+    utils::{SpawnEstimate, SpawnEvent},
+};
+
+#[derive(PartialEq)]
+pub struct EventTimers {
+    compact: bool,
+}
+
+impl EventTimers {
+    pub fn new() -> Self {
+        Self { compact: false }
+    }
+
+    /// Smaller text on a translucent background, for the minimap.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+}
+
+fn describe(estimate: Option<SpawnEstimate>, now: i64) -> String {
+    let Some(estimate) = estimate else {
+        return "learning...".to_string();
+    };
+
+    let from = (estimate.from - now) as f32 / 60.0;
+    let to = (estimate.to - now) as f32 / 60.0;
+    if from > 0.0 {
+        format!("likely in {:.0}–{:.0} min", from, to)
+    } else if to > 0.0 {
+        format!("due within {:.0} min", to.max(1.0))
+    } else {
+        "overdue".to_string()
+    }
+}
+
+impl Component for EventTimers {
+    fn render(&self) -> impl IntoElement {
+        let radio = use_radio::<Data, DataChannel>(DataChannel::SpawnLogUpdate);
+        let spawn_log = radio.slice_current(|s| &s.spawn_log);
+        let marker_state = radio.slice(DataChannel::MapMarkersUpdate, |s| &s.map_markers.markers);
+
+        // Countdowns are in minutes, so re-rendering every 30 seconds is plenty
+        let mut tick = use_state(|| 0u32);
+        use_hook(|| {
+            spawn(async move {
+                loop {
+                    smol::Timer::after(Duration::from_secs(30)).await;
+                    *tick.write() += 1;
+                }
+            });
+        });
+        let _ = tick();
+
+        let now = chrono::Utc::now().timestamp();
+        let font_size = if self.compact { 10.0 } else { 12.0 };
+
+        rect()
+            .spacing(2.0)
+            .maybe(self.compact, |rect| {
+                rect.padding((4.0, 6.0))
+                    .corner_radius(4.0)
+                    .background(Color::from_hex(colors::BACKGROUND).unwrap().with_a(191))
+            })
+            .children(
+                SpawnEvent::ALL
+                    .into_iter()
+                    .map(|event| {
+                        let active = marker_state
+                            .read()
+                            .values()
+                            .any(|marker| marker.r#type() == event.marker_type());
+
+                        rect()
+                            .spacing(6.0)
+                            .direction(Direction::Horizontal)
+                            .children([
+                                label()
+                                    .width(Size::px(if self.compact { 48.0 } else { 60.0 }))
+                                    .font_size(font_size)
+                                    .font_weight(FontWeight::BOLD)
+                                    .color(Color::from_hex(colors::TEXT).unwrap())
+                                    .text(event.title())
+                                    .into(),
+                                label()
+                                    .font_size(font_size)
+                                    .font_weight(FontWeight::BOLD)
+                                    .color(
+                                        Color::from_hex(if active {
+                                            "#aaee32"
+                                        } else {
+                                            "#6b655f"
+                                        })
+                                        .unwrap(),
+                                    )
+                                    .text(if active {
+                                        "active".to_string()
+                                    } else {
+                                        describe(spawn_log.read().estimate(event), now)
+                                    })
+                                    .into(),
+                            ])
+                            .into()
+                    })
+                    .collect::<Vec<Element>>(),
+            )
+    }
+}
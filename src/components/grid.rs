@@ -1,14 +1,13 @@
-use std::cmp;
-
 use freya::prelude::*;
 
-use crate::utils::number_to_letters;
+use crate::utils::{GridPosition, MapGeometry};
 
 #[derive(Clone, PartialEq)]
 pub struct Grid {
     map_size: f32,
     margin: f32,
     zoom: Option<Readable<f32>>,
+    rotation: Option<Readable<f32>>,
 }
 
 impl Grid {
@@ -17,6 +16,7 @@ impl Grid {
             map_size,
             margin,
             zoom: None,
+            rotation: None,
         }
     }
 
@@ -24,25 +24,35 @@ impl Grid {
         self.zoom = Some(zoom.into());
         self
     }
-}
 
-const GRID_CELL_SIZE: f32 = 146.25;
+    /// Rotation of the map in degrees, labels are turned back by it to stay upright.
+    pub fn rotation(mut self, rotation: impl Into<Readable<f32>>) -> Self {
+        self.rotation = Some(rotation.into());
+        self
+    }
+}
 
 impl Component for Grid {
     fn render(&self) -> impl IntoElement {
         let text_margin = 4.0;
 
-        let active_area_size = self.map_size as f32 - (self.margin * 2.0);
+        let geometry = MapGeometry::new(self.map_size - (self.margin * 2.0), self.margin);
+        let active_area_size = geometry.world_size;
 
-        let cells = cmp::max(1, ((active_area_size / GRID_CELL_SIZE).floor()) as u32);
+        let cells = geometry.cells();
 
-        let converted_grid_size = active_area_size / cells as f32;
+        let converted_grid_size = geometry.cell_size();
 
         let zoom = use_hook(|| {
             self.zoom
                 .clone()
                 .unwrap_or_else(|| Readable::from_value(1.0))
         });
+        let rotation = self
+            .rotation
+            .as_ref()
+            .map(|rotation| *rotation.read())
+            .unwrap_or(0.0);
 
         rect()
             .width(Size::Fill)
@@ -84,7 +94,15 @@ impl Component for Grid {
                                     .font_size(-4.44 * *zoom.read() + 19.11)
                                     .font_weight(FontWeight::BOLD)
                                     .color(Color::from_hex("#19191980").unwrap())
-                                    .text(format!("{}{}", number_to_letters(i), j))
+                                    .rotate(rotation)
+                                    .text(
+                                        GridPosition {
+                                            column: i,
+                                            row: j,
+                                            offset: (0.0, 0.0),
+                                        }
+                                        .to_string(),
+                                    )
                                     .position(
                                         Position::new_absolute()
                                             .left(
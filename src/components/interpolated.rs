@@ -0,0 +1,30 @@
+use std::{cell::RefCell, rc::Rc, time::Instant};
+
+use freya::{animation::*, prelude::*};
+
+use crate::utils::{INTERPOLATION_DURATION, Interpolation, Pose};
+
+/// Pose that glides towards `target` whenever it changes, re-rendering until it arrives.
+/// `snap` jumps straight to the target, e.g. while scrubbing through history.
+pub fn use_interpolated(target: Pose, snap: bool) -> Pose {
+    let interpolation = use_hook(|| Rc::new(RefCell::new(Interpolation::new(target))));
+    // Only drives frames, it stops by itself once the glide is over
+    let mut frames = use_animation(|_| {
+        AnimNum::new(0.0, 1.0)
+            .function(Function::Linear)
+            .time(INTERPOLATION_DURATION.as_millis() as u64)
+    });
+    let _ = frames.read().value();
+
+    let now = Instant::now();
+    let mut interpolation = interpolation.borrow_mut();
+    if snap {
+        interpolation.snap(target);
+    } else {
+        interpolation.retarget(target, now);
+        if interpolation.is_animating() {
+            frames.start();
+        }
+    }
+    interpolation.sample(now)
+}
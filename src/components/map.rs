@@ -1,17 +1,37 @@
-use std::collections::HashMap;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+    time::Instant,
+};
 
 use euclid::Point2D;
-use freya::{prelude::*, radio::use_radio};
-use rustplus_rs::{AppMarker, AppMarkerType, app_map::Monument, app_team_info::Note};
+use freya::{animation::*, prelude::*, radio::use_radio};
+use rustplus_rs::{AppMarker, AppMarkerType, Vector4, app_map::Monument, app_team_info::Note};
 
 use crate::{
-    Data, DataChannel, TeamMember,
+    Data, DataChannel, TeamMember, colors,
     components::{
-        DragableCanvas, Grid,
+        Button, DragableCanvas, DrawEvent, EdgeArrows, Grid, MonumentPanel, TargetPanel, follow,
         markers::{self},
     },
+    utils::{
+        Annotation, AnnotationDraft, AnnotationShape, GRID_CELL_SIZE, Geofence, GeofenceDraft,
+        HistorySnapshot, INTERPOLATION_DURATION, Interpolation, MapGeometry, MapImage, MapTarget,
+        Pose, TimelineEvent, TimelineEventKind, TrailPoint, VisibleBounds, distance, monument_at,
+        target_at,
+    },
 };
 
+/// Screen distance in pixels within which the pointer picks up a target.
+const TARGET_HIT_RADIUS: f32 = 12.0;
+/// Screen pixels kept around the viewport so markers and labels don't pop in at the edges.
+const CULL_PADDING: f32 = 64.0;
+/// Vending machines collapse into count badges below this zoom.
+const CLUSTER_MAX_ZOOM: f32 = 0.8;
+/// Screen size of the cells vending machines are grouped in.
+const CLUSTER_CELL_SIZE: f32 = 48.0;
+
 #[derive(PartialEq)]
 pub struct Map {
     center: Readable<bool>,
@@ -22,9 +42,21 @@ pub struct Map {
     deaths: Readable<bool>,
     monuments: Readable<bool>,
     shops: Readable<bool>,
+    zones: Readable<bool>,
+    trails: Readable<bool>,
+    trail_minutes: Readable<f32>,
+    heatmap: Readable<bool>,
+    heatmap_member: Readable<Option<u64>>,
+    geofences: Readable<bool>,
+    annotations: Readable<bool>,
 
     background_opacity: Readable<f32>,
     zoom: Option<Writable<f32>>,
+    rotation: Readable<f32>,
+
+    snapshot: Option<HistorySnapshot>,
+    on_draw: Option<EventHandler<DrawEvent>>,
+    on_click: Option<EventHandler<Point2D<f32, ()>>>,
 }
 
 impl Map {
@@ -38,9 +70,21 @@ impl Map {
             deaths: false.into(),
             monuments: false.into(),
             shops: false.into(),
+            zones: false.into(),
+            trails: false.into(),
+            trail_minutes: 10.0.into(),
+            heatmap: false.into(),
+            heatmap_member: None.into(),
+            geofences: false.into(),
+            annotations: false.into(),
 
             background_opacity: 100.0.into(),
             zoom: None,
+            rotation: 0.0.into(),
+
+            snapshot: None,
+            on_draw: None,
+            on_click: None,
         }
     }
 
@@ -79,6 +123,42 @@ impl Map {
         self
     }
 
+    pub fn zones(mut self, zones: impl Into<Readable<bool>>) -> Self {
+        self.zones = zones.into();
+        self
+    }
+
+    pub fn trails(mut self, trails: impl Into<Readable<bool>>) -> Self {
+        self.trails = trails.into();
+        self
+    }
+
+    pub fn trail_minutes(mut self, trail_minutes: impl Into<Readable<f32>>) -> Self {
+        self.trail_minutes = trail_minutes.into();
+        self
+    }
+
+    pub fn heatmap(mut self, heatmap: impl Into<Readable<bool>>) -> Self {
+        self.heatmap = heatmap.into();
+        self
+    }
+
+    /// Limits the heatmap to a single teammate, `None` shows everyone.
+    pub fn heatmap_member(mut self, heatmap_member: impl Into<Readable<Option<u64>>>) -> Self {
+        self.heatmap_member = heatmap_member.into();
+        self
+    }
+
+    pub fn geofences(mut self, geofences: impl Into<Readable<bool>>) -> Self {
+        self.geofences = geofences.into();
+        self
+    }
+
+    pub fn annotations(mut self, annotations: impl Into<Readable<bool>>) -> Self {
+        self.annotations = annotations.into();
+        self
+    }
+
     pub fn background_opacity(mut self, background_opacity: impl Into<Readable<f32>>) -> Self {
         self.background_opacity = background_opacity.into();
         self
@@ -88,18 +168,52 @@ impl Map {
         self.zoom = Some(zoom.into());
         self
     }
+
+    /// Turns the map around its center so this compass bearing points up, in degrees.
+    /// Grid labels are turned back so they stay upright.
+    pub fn rotation(mut self, rotation: impl Into<Readable<f32>>) -> Self {
+        self.rotation = rotation.into();
+        self
+    }
+
+    /// Shows a recorded snapshot instead of the live team, markers and deaths.
+    pub fn snapshot(mut self, snapshot: Option<HistorySnapshot>) -> Self {
+        self.snapshot = snapshot;
+        self
+    }
+
+    /// Turns left dragging into drawing, reporting points in world coordinates.
+    pub fn on_draw(mut self, on_draw: Option<EventHandler<DrawEvent>>) -> Self {
+        self.on_draw = on_draw;
+        self
+    }
+
+    /// Reports left clicks in world coordinates while keeping dragging for panning.
+    pub fn on_click(mut self, on_click: Option<EventHandler<Point2D<f32, ()>>>) -> Self {
+        self.on_click = on_click;
+        self
+    }
 }
 
 impl Component for Map {
     fn render(&self) -> impl IntoElement {
-        let radio = use_radio::<Data, DataChannel>(DataChannel::InfoStateUpdate);
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::InfoStateUpdate);
         let info_state = radio.slice_current(|s| &s.info_state);
         let map_state = radio.slice(DataChannel::MapStateUpdate, |s| &s.map_state);
         let monuments = radio.slice(DataChannel::MapStateUpdate, |s| &s.map_state.monuments);
         let map_notes = radio.slice(DataChannel::MapNotesUpdate, |s| &s.team_info.map_notes);
         let team_members = radio.slice(DataChannel::TeamMembersUpdate, |s| &s.team_info.members);
+        let trails = radio.slice(DataChannel::TrailsUpdate, |s| &s.trails);
+        let timeline = radio.slice(DataChannel::TimelineUpdate, |s| &s.timeline);
+        let geofence_list = radio.slice(DataChannel::GeofencesUpdate, |s| &s.geofences);
+        let geofence_draft = radio.slice(DataChannel::GeofenceDraftUpdate, |s| &s.geofence_draft);
+        let annotation_list = radio.slice(DataChannel::AnnotationsUpdate, |s| &s.annotations);
+        let annotation_draft =
+            radio.slice(DataChannel::AnnotationDraftUpdate, |s| &s.annotation_draft);
+        let measure_route = radio.slice(DataChannel::MeasureUpdate, |s| &s.measure_route);
         let user_data = radio.slice(DataChannel::UserDataUpdate, |s| &s.user_data);
         let marker_state = radio.slice(DataChannel::MapMarkersUpdate, |s| &s.map_markers.markers);
+        let mut map_focus = radio.slice_mut(DataChannel::MapFocusUpdate, |s| &mut s.map_focus);
 
         let (map_size, margin) = match info_state.read().map_size {
             Some(map_size) => {
@@ -128,42 +242,320 @@ impl Component for Map {
             .unwrap_or("0".to_string())
             .parse()
             .unwrap_or(0);
-        let me_radio = radio.slice(DataChannel::TeamMemberUpdate(me_steam_id), |s| {
-            &s.team_info.members
+        let follow_target = radio.slice(DataChannel::FollowUpdate, |s| &s.follow_target);
+        // The camera glides along with the followed marker instead of jumping every poll
+        let camera = use_hook(|| Rc::new(RefCell::new(None::<Interpolation>)));
+        let center_on = {
+            let zoom = zoom.clone();
+            move |pose: Pose| {
+                // Scale the position and compensate for scale offset
+                // When content is scaled from top-left, we need to offset
+                // by half the size change to keep the target centered
+                let current_zoom = *zoom.peek();
+                let scale_offset = map_size * (current_zoom - 1.0) / 2.0;
+                let mut pos = pos;
+                *pos.write() = Point2D::new(
+                    -(pose.x + margin) * current_zoom + scale_offset,
+                    -(map_size - pose.y - margin) * current_zoom + scale_offset,
+                );
+            }
+        };
+        // Runs only while the camera glides
+        let mut camera_frames = use_animation(|_| {
+            AnimNum::new(0.0, 1.0)
+                .function(Function::Linear)
+                .time(INTERPOLATION_DURATION.as_millis() as u64)
+        });
+        use_side_effect({
+            let camera = camera.clone();
+            let center = self.center.clone();
+            let center_on = center_on.clone();
+            move || {
+                let _ = camera_frames.read().value();
+                let pose = match camera.borrow_mut().as_mut() {
+                    Some(camera) if camera.is_animating() => camera.sample(Instant::now()),
+                    _ => return,
+                };
+                if *center.peek() {
+                    center_on(pose);
+                }
+            }
         });
         use_side_effect({
             let center = self.center.clone();
             let zoom = zoom.clone();
+            let camera = camera.clone();
             move || {
-                let binding = me_radio.read();
-                let me = binding.get(&me_steam_id);
-                if me.is_none() {
+                // Follows the local player unless another target was picked
+                let target = follow_target
+                    .read()
+                    .clone()
+                    .unwrap_or(MapTarget::Member(me_steam_id));
+                let followed = match &target {
+                    MapTarget::Member(steam_id) => radio
+                        .slice(DataChannel::TeamMemberUpdate(*steam_id), |s| {
+                            &s.team_info.members
+                        })
+                        .read()
+                        .get(steam_id)
+                        .map(|member| (member.x, member.y)),
+                    MapTarget::Marker(id) => radio
+                        .slice(DataChannel::MarkerUpdate(*id), |s| &s.map_markers.markers)
+                        .read()
+                        .get(id)
+                        .map(|marker| (marker.x, marker.y)),
+                    MapTarget::Place { x, y, .. } => Some((*x, *y)),
+                };
+                let Some((x, y)) = followed else {
                     return;
+                };
+
+                let now = Instant::now();
+                let target = Pose::new(x, y);
+                let pose = {
+                    let mut camera = camera.borrow_mut();
+                    let camera = camera.get_or_insert_with(|| Interpolation::new(target));
+                    camera.retarget(target, now);
+                    if camera.is_animating() {
+                        camera_frames.start();
+                    }
+                    camera.sample(now)
+                };
+                // Re-run on zoom changes so the target stays centred
+                let _ = zoom.read();
+                if *center.read() {
+                    center_on(pose);
                 }
+            }
+        });
 
-                let me = me.unwrap();
+        // Focus requests (e.g. from the timeline) only apply to the interactable map
+        // and last until it is unmounted.
+        let focus_pos = move |focus: Point2D<f32, ()>, current_zoom: f32| {
+            let scale_offset = map_size * (current_zoom - 1.0) / 2.0;
+            Point2D::new(
+                -(focus.x + margin) * current_zoom + scale_offset,
+                -(map_size - focus.y - margin) * current_zoom + scale_offset,
+            )
+        };
+        let initial_pos = match *map_focus.read() {
+            Some(focus) if self.interactable => Some(focus_pos(focus, *zoom.peek())),
+            _ => None,
+        };
+        use_side_effect({
+            let zoom = zoom.clone();
+            let interactable = self.interactable;
+            let map_focus = map_focus.clone();
+            move || {
+                if !interactable {
+                    return;
+                }
+                if let Some(focus) = *map_focus.read() {
+                    *pos.write() = focus_pos(focus, *zoom.peek());
+                }
+            }
+        });
+        use_drop(move || {
+            if map_focus.read().is_some() {
+                *map_focus.write() = None;
+            }
+        });
 
-                let point: Point2D<f32, ()> =
-                    Point2D::new(-(me.x + margin), -(map_size - me.y - margin));
-                if *center.read() {
-                    // Scale the position and compensate for scale offset
-                    // When content is scaled from top-left, we need to offset
-                    // by half the size change to keep the target centered
-                    let current_zoom = *zoom.read();
-                    let scale_offset = map_size * (current_zoom - 1.0) / 2.0;
-                    let scaled_point = Point2D::new(
-                        point.x * current_zoom + scale_offset,
-                        point.y * current_zoom + scale_offset,
-                    );
-                    *pos.write() = scaled_point;
+        let (map_notes, team_members, marker_state) = match &self.snapshot {
+            Some(snapshot) => (
+                Readable::from_value(
+                    snapshot
+                        .deaths
+                        .iter()
+                        .map(|death| Note {
+                            r#type: 0,
+                            x: death.x,
+                            y: death.y,
+                            ..Default::default()
+                        })
+                        .collect::<Vec<Note>>(),
+                ),
+                Readable::from_value(
+                    snapshot
+                        .members
+                        .iter()
+                        .map(|member| {
+                            (
+                                member.steam_id,
+                                TeamMember {
+                                    steam_id: member.steam_id,
+                                    name: member.name.clone(),
+                                    x: member.x,
+                                    y: member.y,
+                                    is_online: member.is_online,
+                                    spawn_time: 0,
+                                    is_alive: member.is_alive,
+                                    death_time: 0,
+                                    profile_icon: None,
+                                },
+                            )
+                        })
+                        .collect::<HashMap<u64, TeamMember>>(),
+                ),
+                Readable::from_value(
+                    snapshot
+                        .markers
+                        .iter()
+                        .map(|marker| {
+                            (
+                                marker.id,
+                                AppMarker {
+                                    id: marker.id,
+                                    r#type: marker.marker_type,
+                                    x: marker.x,
+                                    y: marker.y,
+                                    rotation: marker.rotation,
+                                    radius: marker.radius,
+                                    color1: marker
+                                        .color1
+                                        .map(|[x, y, z, w]| Vector4 { x, y, z, w }),
+                                    color2: marker
+                                        .color2
+                                        .map(|[x, y, z, w]| Vector4 { x, y, z, w }),
+                                    alpha: marker.alpha,
+                                    ..Default::default()
+                                },
+                            )
+                        })
+                        .collect::<HashMap<u32, AppMarker>>(),
+                ),
+            ),
+            None => (
+                map_notes.into_readable(),
+                team_members.into_readable(),
+                marker_state.into_readable(),
+            ),
+        };
+
+        let geometry = MapGeometry::new(map_size - margin * 2.0, margin);
+
+        let on_draw = self.on_draw.clone().map(|on_draw| {
+            EventHandler::new(move |event: DrawEvent| {
+                on_draw.call(event.map(|point| {
+                    let (x, y) = geometry.content_to_world(point.x, point.y);
+                    Point2D::new(x, y)
+                }));
+            })
+        });
+
+        let to_world = move |point: Point2D<f32, ()>| geometry.content_to_world(point.x, point.y);
+        // Pointer hit area around targets, kept constant on screen regardless of zoom
+        let target_at_point = {
+            let zoom = zoom.clone();
+            move |point: (f32, f32)| {
+                let data = radio.read();
+                target_at(
+                    point,
+                    TARGET_HIT_RADIUS / *zoom.peek(),
+                    &data.team_info.members,
+                    &data.map_markers.markers,
+                    &data.team_info.map_notes,
+                    &data.map_state.monuments,
+                )
+            }
+        };
+
+        let on_click = match self.on_click.clone() {
+            Some(on_click) => Some(EventHandler::new(move |point: Point2D<f32, ()>| {
+                let (x, y) = to_world(point);
+                on_click.call(Point2D::new(x, y));
+            })),
+            // Clicking a monument opens its details, any other target is pinned or unpinned
+            None if self.interactable => Some(EventHandler::new({
+                let target_at_point = target_at_point.clone();
+                let zoom = zoom.clone();
+                let monuments = self.monuments.clone();
+                move |point: Point2D<f32, ()>| {
+                    let point = to_world(point);
+                    let target = target_at_point(point);
+                    if *monuments.peek() && matches!(target, None | Some(MapTarget::Place { .. })) {
+                        let monument = monument_at(
+                            point,
+                            TARGET_HIT_RADIUS / *zoom.peek(),
+                            &radio.read().map_state.monuments,
+                        );
+                        if monument.is_some() || radio.read().selected_monument.is_some() {
+                            radio
+                                .write_channel(DataChannel::MonumentSelectUpdate)
+                                .selected_monument = monument.clone();
+                        }
+                        if monument.is_some() {
+                            return;
+                        }
+                    }
+                    let Some(target) = target else {
+                        return;
+                    };
+                    let mut data = radio.write_channel(DataChannel::TargetsUpdate);
+                    if data.pinned_targets.contains(&target) {
+                        data.pinned_targets.retain(|pinned| *pinned != target);
+                    } else {
+                        data.pinned_targets.push(target);
+                    }
+                }
+            })),
+            None => None,
+        };
+        let on_hover = self.interactable.then(|| {
+            EventHandler::new(move |point: Option<Point2D<f32, ()>>| {
+                let target = point.and_then(|point| target_at_point(to_world(point)));
+                if radio.read().hovered_target != target {
+                    radio
+                        .write_channel(DataChannel::TargetsUpdate)
+                        .hovered_target = target;
                 }
+            })
+        });
+        let mut viewport = use_state(|| Area::default());
+        // Right clicking the map offers to pin or follow that spot
+        let mut context_menu: State<Option<(Point2D<f32, ()>, String, MapTarget)>> =
+            use_state(|| None);
+        let on_secondary_click = self.interactable.then(|| {
+            EventHandler::new(
+                move |(point, global): (Point2D<f32, ()>, Point2D<f32, ()>)| {
+                    let (x, y) = to_world(point);
+                    let origin = viewport.read().origin;
+                    let name = geometry.grid_position(x, y).precise();
+                    context_menu.set(Some((
+                        Point2D::new(global.x - origin.x, global.y - origin.y),
+                        name.clone(),
+                        MapTarget::Place { name, x, y },
+                    )));
+                },
+            )
+        });
+        let mut canvas_area = use_state(|| Area::default());
+        let zoom_readable: Readable<f32> = zoom.clone().into();
+        // Bounds only change once the view crosses a cell, so panning doesn't re-render
+        // every culled layer each frame
+        let bounds = use_memo({
+            let zoom = zoom_readable.clone();
+            move || {
+                visible_bounds(
+                    map_size,
+                    margin,
+                    *pos.read(),
+                    *zoom.read(),
+                    *canvas_area.read(),
+                )
             }
         });
+        let map_viewport = MapViewport { bounds };
 
-        let image_bytes: &'static [u8] =
-            Box::leak(map_state.read().jpg_image.clone().into_boxed_slice());
+        let image = map_state.read().image.clone();
 
         rect()
+            .on_sized(move |e: Event<SizedEventData>| *viewport.write() = e.area)
+            .on_mouse_down(move |e: Event<MouseEventData>| {
+                if e.button == Some(MouseButton::Left) && context_menu.peek().is_some() {
+                    context_menu.set(None);
+                }
+            })
             .overflow(Overflow::Clip)
             .width(Size::Fill)
             .height(Size::Fill)
@@ -181,77 +573,334 @@ impl Component for Map {
                     .unwrap(),
                 )
             })
-            .children([DragableCanvas::new()
-                .interactable(self.interactable)
-                .children_size(Point2D::new(map_size, map_size))
-                .zoom(zoom.clone())
-                .pos(pos)
-                .child(ImageLayer {
-                    image_bytes,
-                    map_size,
-                    background_opacity: self.background_opacity.clone().into(),
-                })
-                .child(GridLayer {
-                    grid: self.grid.clone(),
-                    map_size,
-                    margin,
-                    zoom: zoom.clone().into(),
-                })
-                .child(MonumentLayer {
-                    monuments: self.monuments.clone(),
+            .child(
+                RotatedCanvas::new(
+                    self.rotation.clone(),
+                    viewport.into(),
+                    canvas_area.into(),
+                )
+                .child(
+                    DragableCanvas::new()
+                        .interactable(self.interactable)
+                        .children_size(Point2D::new(map_size, map_size))
+                        .zoom(zoom.clone())
+                        .pos(pos)
+                        .initial_pos(initial_pos)
+                        .on_draw(on_draw)
+                        .on_click(on_click)
+                        .on_hover(on_hover)
+                        .on_secondary_click(on_secondary_click)
+                        .child(ImageLayer {
+                            image,
+                            map_size,
+                            zoom: zoom_readable.clone(),
+                            background_opacity: self.background_opacity.clone().into(),
+                        })
+                        .child(GridLayer {
+                            grid: self.grid.clone(),
+                            map_size,
+                            margin,
+                            zoom: zoom.clone().into(),
+                            rotation: self.rotation.clone(),
+                        })
+                        .child(MonumentLayer {
+                            monuments: self.monuments.clone(),
 
-                    map_size,
-                    margin,
-                    monument_list: monuments.into_readable(),
-                })
-                .child(MapNoteLayer {
-                    markers: self.markers.clone(),
-                    deaths: self.deaths.clone(),
-                    map_size: map_size,
-                    margin: margin,
-                    map_notes: map_notes.into_readable(),
-                })
-                .child(TeamLayer {
-                    members: team_members.into_readable(),
+                            map_size,
+                            margin,
+                            viewport: map_viewport.clone(),
+                            monument_list: monuments.into_readable(),
+                        })
+                        .child(GeofenceLayer {
+                            geofences: self.geofences.clone(),
 
-                    map_size,
-                    margin,
-                    zoom: zoom.clone().into(),
-                    center: self.center.clone(),
-                })
-                .child(MarkerLayer {
-                    shops: self.shops.clone(),
-                    markers: self.markers.clone(),
+                            map_size,
+                            margin,
+                            geofence_list: geofence_list.into_readable(),
+                            draft: geofence_draft.into_readable(),
+                        })
+                        .child(AnnotationLayer {
+                            annotations: self.annotations.clone(),
+
+                            map_size,
+                            margin,
+                            annotation_list: annotation_list.into_readable(),
+                            draft: annotation_draft.into_readable(),
+                        })
+                        .child(MeasureLayer {
+                            map_size,
+                            margin,
+                            route: measure_route.into_readable(),
+                        })
+                        .child(HeatmapLayer {
+                            heatmap: self.heatmap.clone(),
+                            heatmap_member: self.heatmap_member.clone(),
+
+                            map_size,
+                            margin,
+                            timeline: timeline.into_readable(),
+                            until: self.snapshot.as_ref().map(|snapshot| snapshot.timestamp),
+                        })
+                        .child(MapNoteLayer {
+                            markers: self.markers.clone(),
+                            deaths: self.deaths.clone(),
+                            map_size: map_size,
+                            margin: margin,
+                            viewport: map_viewport.clone(),
+                            map_notes,
+                        })
+                        .child(TrailLayer {
+                            trails: self.trails.clone(),
+                            trail_minutes: self.trail_minutes.clone(),
+
+                            map_size,
+                            margin,
+                            trail_list: trails.into_readable(),
+                            until: self.snapshot.as_ref().map(|snapshot| snapshot.timestamp),
+                        })
+                        .child(TeamLayer {
+                            members: team_members,
+                            snapshot: self.snapshot.is_some(),
 
+                            map_size,
+                            margin,
+                            zoom: zoom.clone().into(),
+                            center: self.center.clone(),
+                        })
+                        .child(MarkerLayer {
+                            shops: self.shops.clone(),
+                            markers: self.markers.clone(),
+                            zones: self.zones.clone(),
+
+                            map_size,
+                            margin,
+                            viewport: map_viewport,
+
+                            zoom: zoom.into(),
+
+                            marker_list: marker_state,
+                            snapshot: self.snapshot.is_some(),
+                        }),
+                ),
+            )
+            .maybe_child(self.interactable.then(|| MonumentPanel {}))
+            .child(if self.interactable {
+                TargetPanel::new(me_steam_id).into_element()
+            } else {
+                EdgeArrows {
+                    me_steam_id,
                     map_size,
                     margin,
+                    pos: pos.into(),
+                    zoom: zoom_readable,
+                    viewport: viewport.into(),
+                    rotation: self.rotation.clone(),
+                }
+                .into_element()
+            })
+            .maybe_child(context_menu.read().clone().map(|(at, name, target)| {
+                let pin_target = target.clone();
+                rect()
+                    .position(Position::new_absolute().left(at.x).top(at.y))
+                    .layer(Layer::Relative(4))
+                    .padding(6.0)
+                    .spacing(4.0)
+                    .corner_radius(4.0)
+                    .background(Color::from_hex(colors::BACKGROUND).unwrap().with_a(230))
+                    // Keeps the outer handler from closing the menu before a button is pressed
+                    .on_mouse_down(|e: Event<MouseEventData>| e.stop_propagation())
+                    .child(
+                        label()
+                            .font_size(13.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::TEXT).unwrap())
+                            .text(name),
+                    )
+                    .child(
+                        Button::new()
+                            .width(Size::Fill)
+                            .height(Size::px(28.0))
+                            .padding((4.0, 8.0))
+                            .icon(freya_icons::lucide::pin())
+                            .text("PIN TARGET")
+                            .on_press(move |_| {
+                                let mut data = radio.write_channel(DataChannel::TargetsUpdate);
+                                if !data.pinned_targets.contains(&pin_target) {
+                                    data.pinned_targets.push(pin_target.clone());
+                                }
+                                context_menu.set(None);
+                            }),
+                    )
+                    .child(
+                        Button::new()
+                            .width(Size::Fill)
+                            .height(Size::px(28.0))
+                            .padding((4.0, 8.0))
+                            .icon(freya_icons::lucide::locate_fixed())
+                            .text("FOLLOW")
+                            .on_press(move |_| {
+                                follow(radio, Some(target.clone()));
+                                context_menu.set(None);
+                            }),
+                    )
+            }))
+    }
+}
 
-                    zoom: zoom.into(),
+/// Turns the canvas by the map rotation. Reading the rotation only here keeps a turning
+/// heading-up map from re-rendering everything else every frame.
+#[derive(PartialEq)]
+struct RotatedCanvas {
+    rotation: Readable<f32>,
+    viewport: Readable<Area>,
+    canvas_area: Writable<Area>,
+    elements: Vec<Element>,
+}
 
-                    marker_list: marker_state.into_readable(),
-                })
-                .into()])
+impl RotatedCanvas {
+    fn new(rotation: Readable<f32>, viewport: Readable<Area>, canvas_area: Writable<Area>) -> Self {
+        Self {
+            rotation,
+            viewport,
+            canvas_area,
+            elements: Vec::new(),
+        }
     }
 }
 
-#[derive(PartialEq)]
+impl ChildrenExt for RotatedCanvas {
+    fn get_children(&mut self) -> &mut Vec<Element> {
+        &mut self.elements
+    }
+}
+
+impl Component for RotatedCanvas {
+    fn render(&self) -> impl IntoElement {
+        let rotation = *self.rotation.read();
+        // A rotated map is drawn on a square covering the viewport's diagonal so no corners
+        // show, culling then works on that square
+        let canvas_size = if rotation == 0.0 {
+            Size::Fill
+        } else {
+            let viewport = *self.viewport.read();
+            Size::px(viewport.width().hypot(viewport.height()))
+        };
+        let mut canvas_area = self.canvas_area.clone();
+
+        rect()
+            .on_sized(move |e: Event<SizedEventData>| *canvas_area.write() = e.area)
+            .width(canvas_size.clone())
+            .height(canvas_size)
+            .rotate(-rotation)
+            .children(self.elements.clone())
+    }
+}
+
+/// Visible part of the canvas, for layers that skip whatever is off screen.
+#[derive(Clone, PartialEq)]
+struct MapViewport {
+    bounds: Memo<Option<VisibleBounds>>,
+}
+
+impl MapViewport {
+    /// Visible part of the map, `None` until the map has been laid out once.
+    fn visible(&self) -> Option<VisibleBounds> {
+        *self.bounds.read()
+    }
+}
+
+/// World space bounds of the canvas, grown outwards to whole grid cells.
+fn visible_bounds(
+    map_size: f32,
+    margin: f32,
+    pos: Point2D<f32, ()>,
+    zoom: f32,
+    area: Area,
+) -> Option<VisibleBounds> {
+    if area.width() <= 0.0 || area.height() <= 0.0 {
+        return None;
+    }
+
+    let scale_offset = map_size * (zoom - 1.0) / 2.0;
+    let to_content =
+        |screen: f32, length: f32, pos: f32| (screen - length / 2.0 - pos + scale_offset) / zoom;
+    let geometry = MapGeometry::new(map_size - margin * 2.0, margin);
+    let (min_x, max_y) = geometry.content_to_world(
+        to_content(-CULL_PADDING, area.width(), pos.x),
+        to_content(-CULL_PADDING, area.height(), pos.y),
+    );
+    let (max_x, min_y) = geometry.content_to_world(
+        to_content(area.width() + CULL_PADDING, area.width(), pos.x),
+        to_content(area.height() + CULL_PADDING, area.height(), pos.y),
+    );
+
+    let floor = |value: f32| (value / GRID_CELL_SIZE).floor() * GRID_CELL_SIZE;
+    let ceil = |value: f32| (value / GRID_CELL_SIZE).ceil() * GRID_CELL_SIZE;
+    Some(VisibleBounds {
+        min_x: floor(min_x),
+        max_x: ceil(max_x),
+        min_y: floor(min_y),
+        max_y: ceil(max_y),
+    })
+}
+
+fn in_view(visible: &Option<VisibleBounds>, x: f32, y: f32) -> bool {
+    visible.is_none_or(|visible| visible.contains(x, y))
+}
+
+/// Groups points sharing a `cell_size` square, keeping the input order within each group.
+fn cluster<T>(items: Vec<(T, f32, f32)>, cell_size: f32) -> Vec<Vec<(T, f32, f32)>> {
+    let mut cells: HashMap<(i32, i32), usize> = HashMap::new();
+    let mut clusters: Vec<Vec<(T, f32, f32)>> = Vec::new();
+    for item in items {
+        let cell = (
+            (item.1 / cell_size).floor() as i32,
+            (item.2 / cell_size).floor() as i32,
+        );
+        match cells.get(&cell) {
+            Some(index) => clusters[*index].push(item),
+            None => {
+                cells.insert(cell, clusters.len());
+                clusters.push(vec![item]);
+            }
+        }
+    }
+    clusters
+}
+
 struct ImageLayer {
-    image_bytes: &'static [u8],
+    image: Option<MapImage>,
     map_size: f32,
+    zoom: Readable<f32>,
     background_opacity: Readable<f32>,
 }
 
+impl PartialEq for ImageLayer {
+    fn eq(&self, other: &Self) -> bool {
+        self.image.as_ref().map(MapImage::key) == other.image.as_ref().map(MapImage::key)
+            && self.map_size == other.map_size
+            && self.zoom == other.zoom
+            && self.background_opacity == other.background_opacity
+    }
+}
+
 impl Component for ImageLayer {
     fn render(&self) -> impl IntoElement {
+        // Zoomed out views and the minimap get a downscaled level instead of the full image
+        let level = self
+            .image
+            .as_ref()
+            .map(|image| image.level_for(self.map_size * *self.zoom.read()).clone());
+
         rect()
             .width(Size::px(self.map_size))
             .height(Size::px(self.map_size))
             .opacity(*self.background_opacity.read() / 100.0)
-            .child(
-                ImageViewer::new(("map", self.image_bytes))
+            .maybe_child(level.map(|level| {
+                ImageViewer::new((level.key, level.bytes))
                     .width(Size::px(self.map_size))
-                    .height(Size::px(self.map_size)),
-            )
+                    .height(Size::px(self.map_size))
+            }))
     }
 }
 
@@ -261,6 +910,7 @@ struct GridLayer {
     map_size: f32,
     margin: f32,
     zoom: Readable<f32>,
+    rotation: Readable<f32>,
 }
 
 impl Component for GridLayer {
@@ -273,7 +923,11 @@ impl Component for GridLayer {
             .width(Size::px(self.map_size))
             .height(Size::px(self.map_size))
             .layer(1)
-            .child(Grid::new(self.map_size, self.margin).zoom(self.zoom.clone()))
+            .child(
+                Grid::new(self.map_size, self.margin)
+                    .zoom(self.zoom.clone())
+                    .rotation(self.rotation.clone()),
+            )
     }
 }
 
@@ -283,6 +937,7 @@ struct MonumentLayer {
 
     map_size: f32,
     margin: f32,
+    viewport: MapViewport,
     monument_list: Readable<Vec<Monument>>,
 }
 
@@ -291,6 +946,7 @@ impl Component for MonumentLayer {
         if *self.monuments.read() == false {
             return rect().into();
         }
+        let visible = self.viewport.visible();
 
         rect()
             .width(Size::px(self.map_size))
@@ -303,6 +959,8 @@ impl Component for MonumentLayer {
                     .filter_map(|monument| {
                         if monument.token == "train_tunnel_display_name" {
                             None
+                        } else if !in_view(&visible, monument.x, monument.y) {
+                            None
                         } else if monument.token.starts_with("assets") {
                             None
                         } else {
@@ -321,6 +979,221 @@ impl Component for MonumentLayer {
     }
 }
 
+#[derive(PartialEq)]
+struct GeofenceLayer {
+    geofences: Readable<bool>,
+
+    map_size: f32,
+    margin: f32,
+    geofence_list: Readable<Vec<Geofence>>,
+    draft: Readable<Option<GeofenceDraft>>,
+}
+
+impl Component for GeofenceLayer {
+    fn render(&self) -> impl IntoElement {
+        let draft = self.draft.read().and_then(|draft| draft.shape());
+        if *self.geofences.read() == false && draft.is_none() {
+            return rect().into();
+        }
+
+        rect()
+            .width(Size::px(self.map_size))
+            .height(Size::px(self.map_size))
+            .layer(2)
+            .maybe(*self.geofences.read(), |rect| {
+                rect.children(
+                    self.geofence_list
+                        .read()
+                        .iter()
+                        .map(|geofence| {
+                            markers::geofence_zone(
+                                geofence.shape,
+                                Some(geofence.name.clone()),
+                                self.margin,
+                                self.map_size,
+                            )
+                            .into()
+                        })
+                        .collect::<Vec<Element>>(),
+                )
+            })
+            .maybe_child(draft.map(|shape| {
+                markers::geofence_zone(shape, None, self.margin, self.map_size)
+            }))
+    }
+}
+
+#[derive(PartialEq)]
+struct AnnotationLayer {
+    annotations: Readable<bool>,
+
+    map_size: f32,
+    margin: f32,
+    annotation_list: Readable<Vec<Annotation>>,
+    draft: Readable<Option<AnnotationDraft>>,
+}
+
+impl Component for AnnotationLayer {
+    fn render(&self) -> impl IntoElement {
+        let draft = self.draft.read().clone();
+        if *self.annotations.read() == false && draft.is_none() {
+            return rect().into();
+        }
+
+        let mut elements = Vec::new();
+        if *self.annotations.read() {
+            for annotation in self.annotation_list.read().iter() {
+                elements.extend(markers::annotation_outline(
+                    &annotation.shape,
+                    annotation.color,
+                    self.margin,
+                    self.map_size,
+                ));
+                elements.push(
+                    markers::annotation_pin(
+                        &annotation.shape,
+                        annotation.icon,
+                        annotation.color,
+                        annotation.label.clone(),
+                        self.margin,
+                        self.map_size,
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        // Unfinished drawings show as an open line with a dot on every placed point
+        if let Some(draft) = draft {
+            let shape = AnnotationShape::Line {
+                points: draft.points.clone(),
+            };
+            elements.extend(markers::annotation_outline(
+                &shape,
+                0,
+                self.margin,
+                self.map_size,
+            ));
+            elements.extend(draft.points.iter().map(|(x, y)| {
+                markers::annotation_pin(
+                    &AnnotationShape::Line {
+                        points: vec![(*x, *y)],
+                    },
+                    0,
+                    0,
+                    String::new(),
+                    self.margin,
+                    self.map_size,
+                )
+                .into()
+            }));
+        }
+
+        rect()
+            .width(Size::px(self.map_size))
+            .height(Size::px(self.map_size))
+            .layer(2)
+            .children(elements)
+    }
+}
+
+#[derive(PartialEq)]
+struct MeasureLayer {
+    map_size: f32,
+    margin: f32,
+    route: Readable<Option<Vec<(f32, f32)>>>,
+}
+
+impl Component for MeasureLayer {
+    fn render(&self) -> impl IntoElement {
+        let Some(route) = self.route.read().clone() else {
+            return rect().into();
+        };
+
+        let segments = route.windows(2).map(|pair| {
+            markers::line_segment(pair[0], pair[1], 2.0, self.margin, self.map_size)
+                .background(Color::WHITE)
+                .into()
+        });
+        let mut total = 0.0;
+        let points = route.iter().enumerate().map(|(i, point)| {
+            if i > 0 {
+                total += distance(route[i - 1], *point);
+            }
+            markers::measure_point(*point, (i > 0).then_some(total), self.margin, self.map_size)
+                .into()
+        });
+
+        rect()
+            .width(Size::px(self.map_size))
+            .height(Size::px(self.map_size))
+            .layer(2)
+            .children(segments.chain(points).collect::<Vec<Element>>())
+    }
+}
+
+#[derive(PartialEq)]
+struct HeatmapLayer {
+    heatmap: Readable<bool>,
+    heatmap_member: Readable<Option<u64>>,
+
+    map_size: f32,
+    margin: f32,
+    timeline: Readable<Vec<TimelineEvent>>,
+    until: Option<i64>,
+}
+
+impl Component for HeatmapLayer {
+    fn render(&self) -> impl IntoElement {
+        if *self.heatmap.read() == false {
+            return rect().into();
+        }
+
+        // Every teammate death of the wipe is kept in the timeline, each one counts so spots
+        // people keep dying at stand out
+        let member_filter = *self.heatmap_member.read();
+        let mut cells: HashMap<(i32, i32), u32> = HashMap::new();
+        for event in self.timeline.read().iter() {
+            let TimelineEventKind::TeammateDied { steam_id, .. } = &event.kind else {
+                continue;
+            };
+            if member_filter.is_some_and(|member| member != *steam_id)
+                || self.until.is_some_and(|until| event.timestamp > until)
+            {
+                continue;
+            }
+            if let (Some(x), Some(y)) = (event.x, event.y) {
+                let cell = (
+                    (x / markers::HEATMAP_CELL_SIZE).floor() as i32,
+                    (y / markers::HEATMAP_CELL_SIZE).floor() as i32,
+                );
+                *cells.entry(cell).or_default() += 1;
+            }
+        }
+        let max = cells.values().copied().max().unwrap_or(1) as f32;
+
+        rect()
+            .width(Size::px(self.map_size))
+            .height(Size::px(self.map_size))
+            .layer(2)
+            .children(
+                cells
+                    .into_iter()
+                    .map(|((cell_x, cell_y), count)| {
+                        markers::heat_spot(
+                            (cell_x as f32 + 0.5) * markers::HEATMAP_CELL_SIZE,
+                            (cell_y as f32 + 0.5) * markers::HEATMAP_CELL_SIZE,
+                            count as f32 / max,
+                            self.margin,
+                            self.map_size,
+                        )
+                        .into()
+                    })
+                    .collect::<Vec<Element>>(),
+            )
+    }
+}
+
 #[derive(PartialEq)]
 struct MapNoteLayer {
     markers: Readable<bool>,
@@ -328,6 +1201,7 @@ struct MapNoteLayer {
 
     map_size: f32,
     margin: f32,
+    viewport: MapViewport,
     map_notes: Readable<Vec<Note>>,
 }
 
@@ -336,6 +1210,7 @@ impl Component for MapNoteLayer {
         if *self.markers.read() == false && *self.deaths.read() == false {
             return rect().into();
         }
+        let visible = self.viewport.visible();
 
         rect()
             .width(Size::px(self.map_size))
@@ -345,6 +1220,7 @@ impl Component for MapNoteLayer {
                 self.map_notes
                     .read()
                     .iter()
+                    .filter(|map_note| in_view(&visible, map_note.x, map_note.y))
                     .filter_map(|map_note| match map_note.r#type {
                         0 => {
                             if *self.deaths.read() == false {
@@ -373,9 +1249,71 @@ impl Component for MapNoteLayer {
     }
 }
 
+#[derive(PartialEq)]
+struct TrailLayer {
+    trails: Readable<bool>,
+    trail_minutes: Readable<f32>,
+
+    map_size: f32,
+    margin: f32,
+    trail_list: Readable<HashMap<u64, VecDeque<TrailPoint>>>,
+    until: Option<i64>,
+}
+
+impl Component for TrailLayer {
+    fn render(&self) -> impl IntoElement {
+        if *self.trails.read() == false {
+            return rect().into();
+        }
+
+        let duration = (*self.trail_minutes.read() * 60.0) as i64;
+        let until = self.until.unwrap_or_else(|| chrono::Utc::now().timestamp());
+        let cutoff = until - duration;
+        let color = Color::from_hex("#aaee32").unwrap();
+
+        rect()
+            .width(Size::px(self.map_size))
+            .height(Size::px(self.map_size))
+            .layer(2)
+            .children(
+                self.trail_list
+                    .read()
+                    .values()
+                    .flat_map(|trail| {
+                        let points = trail
+                            .iter()
+                            .filter(|point| point.timestamp >= cutoff && point.timestamp <= until)
+                            .copied()
+                            .collect::<Vec<TrailPoint>>();
+
+                        points
+                            .windows(2)
+                            .filter(|pair| !pair[0].end)
+                            .filter(|pair| pair[0].x != pair[1].x || pair[0].y != pair[1].y)
+                            .map(|pair| {
+                                // Older segments fade out towards the end of the trail
+                                let age = (pair[1].timestamp - cutoff) as f32 / duration.max(1) as f32;
+                                markers::trail_segment(
+                                    pair[0],
+                                    pair[1],
+                                    color,
+                                    age.clamp(0.1, 1.0),
+                                    self.margin,
+                                    self.map_size,
+                                )
+                                .into()
+                            })
+                            .collect::<Vec<Element>>()
+                    })
+                    .collect::<Vec<Element>>(),
+            )
+    }
+}
+
 #[derive(PartialEq)]
 struct TeamLayer {
     members: Readable<HashMap<u64, TeamMember>>,
+    snapshot: bool,
 
     map_size: f32,
     margin: f32,
@@ -401,6 +1339,7 @@ impl Component for TeamLayer {
 
                         Some(
                             markers::Player::new(member.steam_id, self.map_size, self.margin)
+                                .member(self.snapshot.then(|| member.clone()))
                                 .into(),
                         )
                     }),
@@ -412,70 +1351,94 @@ impl Component for TeamLayer {
 struct MarkerLayer {
     shops: Readable<bool>,
     markers: Readable<bool>,
+    zones: Readable<bool>,
 
     map_size: f32,
     margin: f32,
+    viewport: MapViewport,
 
     zoom: Readable<f32>,
 
-    marker_list: Readable<Vec<AppMarker>>,
+    /// Only re-read when markers are added or removed, each marker follows its own channel.
+    marker_list: Readable<HashMap<u32, AppMarker>>,
+    snapshot: bool,
 }
 
 impl Component for MarkerLayer {
     fn render(&self) -> impl IntoElement {
+        let visible = self.viewport.visible();
+        let zoom = *self.zoom.read();
+        let marker_list = self.marker_list.read();
+
+        // Vending machines are the only markers dense enough to need clustering
+        let vending_machines = marker_list
+            .values()
+            .filter(|marker| *self.shops.read() && marker.r#type() == AppMarkerType::VendingMachine)
+            .filter(|marker| in_view(&visible, marker.x, marker.y))
+            .map(|marker| (marker, marker.x, marker.y))
+            .collect::<Vec<_>>();
+        let groups = if zoom < CLUSTER_MAX_ZOOM {
+            cluster(vending_machines, CLUSTER_CELL_SIZE / zoom)
+        } else {
+            vending_machines.into_iter().map(|machine| vec![machine]).collect()
+        };
+        let vending_elements = groups
+            .into_iter()
+            .map(|group| match group.as_slice() {
+                [(marker, _, _)] => {
+                    markers::VendingMachine::new(marker.id, self.map_size, self.margin)
+                        .marker(self.snapshot.then(|| (*marker).clone()))
+                        .into()
+                }
+                group => {
+                    let count = group.len();
+                    let x = group.iter().map(|(_, x, _)| x).sum::<f32>() / count as f32;
+                    let y = group.iter().map(|(_, _, y)| y).sum::<f32>() / count as f32;
+                    markers::cluster_badge(x, y, count, self.margin, self.map_size).into()
+                }
+            })
+            .collect::<Vec<Element>>();
+
         rect()
             .width(Size::px(self.map_size))
             .height(Size::px(self.map_size))
             .layer(2)
-            .children(self.marker_list.read().iter().filter_map(|marker| {
+            .children(vending_elements)
+            .children(marker_list.values().filter_map(|marker| {
+                let snapshot = self.snapshot.then(|| marker.clone());
                 match marker.r#type() {
-                    AppMarkerType::VendingMachine => {
-                        if *self.shops.read() == false {
+                    AppMarkerType::VendingMachine => None,
+                    AppMarkerType::Player => None,
+                    AppMarkerType::CargoShip => Some(
+                        markers::CargoShip::new(marker.id, self.map_size, self.margin)
+                            .marker(snapshot)
+                            .into(),
+                    ),
+                    AppMarkerType::GenericRadius => {
+                        if *self.zones.read() == false {
                             return None;
                         }
                         Some(
-                            markers::VendingMachine::new(
-                                marker.clone(),
-                                self.map_size,
-                                self.margin,
-                            )
-                            .into(),
+                            markers::GenericRadius::new(marker.id, self.map_size, self.margin)
+                                .marker(snapshot)
+                                .into(),
                         )
                     }
-                    AppMarkerType::Player => None,
-                    AppMarkerType::CargoShip => Some(
-                        markers::CargoShip::new(marker.clone(), self.map_size, self.margin).into(),
-                    ),
-                    AppMarkerType::GenericRadius => None,
-                    marker_type => {
+                    // Not culled, the layer doesn't re-render while they move so their
+                    // position here may be stale
+                    _ => {
                         if *self.markers.read() == false {
                             return None;
                         }
-                        eprintln!("Unknown marker: {:?}", marker);
                         Some(
-                            rect()
-                                .width(Size::px(4.0))
-                                .height(Size::px(4.0))
-                                .corner_radius(CornerRadius::new_all(1000.0))
-                                .background(Color::YELLOW)
-                                .position(
-                                    Position::new_absolute()
-                                        .left((marker.x) - 2.0)
-                                        .top((self.map_size - marker.y) - 2.0),
-                                )
-                                .main_align(Alignment::Center)
-                                .cross_align(Alignment::Center)
-                                .child(
-                                    label()
-                                        .width(Size::px(500.0))
-                                        .text_align(TextAlign::Center)
-                                        // Magic numbers :)
-                                        .font_size(8.864 / *self.zoom.read() + 2.446)
-                                        .font_family("PermanentMarker")
-                                        .color(Color::from_hex("#191919e6").unwrap())
-                                        .text(format!("{:?}", marker_type)),
-                                )
-                                .into(),
+                            markers::EventMarker::new(
+                                marker.id,
+                                self.map_size,
+                                self.margin,
+                                self.zoom.clone(),
+                            )
+                            .marker(snapshot)
+                            .into(),
                         )
                     }
                 }
@@ -0,0 +1,332 @@
+use euclid::{Point2D, Vector2D};
+use freya::{
+    prelude::*,
+    radio::{Radio, use_radio},
+};
+
+use crate::{
+    Data, DataChannel, colors,
+    components::Button,
+    utils::{
+        GridPosition, MapGeometry, MapTarget, ResolvedTarget, bearing, compass_point, distance,
+        format_distance, precise_grid_reference,
+    },
+};
+
+/// Subscribes to the local player and the teammates and markers behind `targets`, so the
+/// overlays re-render when one of them moves.
+fn subscribe<'a>(
+    radio: Radio<Data, DataChannel>,
+    me_steam_id: u64,
+    targets: impl Iterator<Item = &'a MapTarget>,
+) {
+    let _ = radio
+        .slice(DataChannel::TeamMemberUpdate(me_steam_id), |s| {
+            &s.team_info.members
+        })
+        .read();
+    for target in targets {
+        match target {
+            MapTarget::Member(steam_id) => {
+                let _ = radio
+                    .slice(DataChannel::TeamMemberUpdate(*steam_id), |s| {
+                        &s.team_info.members
+                    })
+                    .read();
+            }
+            MapTarget::Marker(id) => {
+                let _ = radio
+                    .slice(DataChannel::MarkerUpdate(*id), |s| &s.map_markers.markers)
+                    .read();
+            }
+            MapTarget::Place { .. } => {}
+        }
+    }
+}
+
+/// Centres the maps on `target`, `None` goes back to following the local player.
+pub fn follow(mut radio: Radio<Data, DataChannel>, target: Option<MapTarget>) {
+    radio.write_channel(DataChannel::FollowUpdate).follow_target = target;
+    radio
+        .write_channel(DataChannel::MapSettingsUpdate)
+        .settings
+        .map_settings
+        .center = true;
+}
+
+fn me_position(data: &Data, me_steam_id: u64) -> Option<(f32, f32)> {
+    data.team_info
+        .members
+        .get(&me_steam_id)
+        .map(|me| (me.x, me.y))
+}
+
+fn resolve(data: &Data, target: &MapTarget) -> Option<ResolvedTarget> {
+    target.resolve(&data.team_info.members, &data.map_markers.markers)
+}
+
+/// Grid reference, distance and compass bearing of a target as seen from the local player.
+fn describe(target: &ResolvedTarget, me: Option<(f32, f32)>, map_size: Option<u32>) -> String {
+    let grid = map_size
+        .map(|map_size| precise_grid_reference(target.x, target.y, map_size as f32))
+        .unwrap_or_else(|| "--".to_string());
+
+    match me {
+        Some(me) => {
+            let bearing = bearing(me, (target.x, target.y));
+            format!(
+                "{}  {}  {}  {} {:.0}°",
+                target.name,
+                grid,
+                format_distance(distance(me, (target.x, target.y))),
+                compass_point(bearing),
+                bearing
+            )
+        }
+        None => format!("{}  {}", target.name, grid),
+    }
+}
+
+/// Hovered and pinned targets in the corner of the main map.
+#[derive(PartialEq)]
+pub struct TargetPanel {
+    me_steam_id: u64,
+}
+
+impl TargetPanel {
+    pub fn new(me_steam_id: u64) -> Self {
+        Self { me_steam_id }
+    }
+}
+
+impl Component for TargetPanel {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::TargetsUpdate);
+        let follow_target = radio.slice(DataChannel::FollowUpdate, |s| &s.follow_target);
+        let center = radio.slice(DataChannel::MapSettingsUpdate, |s| {
+            &s.settings.map_settings.center
+        });
+        let mut grid_text = use_state(String::new);
+
+        let followed = follow_target.read().clone().filter(|_| *center.read());
+        let data = radio.read();
+        subscribe(
+            radio,
+            self.me_steam_id,
+            data.hovered_target.iter().chain(&data.pinned_targets),
+        );
+        let me = me_position(&data, self.me_steam_id);
+        let map_size = data.info_state.map_size;
+
+        let hovered = data
+            .hovered_target
+            .as_ref()
+            .filter(|target| !data.pinned_targets.contains(target))
+            .and_then(|target| resolve(&data, target))
+            .map(|target| (None, target));
+        let pinned = data.pinned_targets.iter().filter_map(|target| {
+            resolve(&data, target).map(|resolved| (Some(target.clone()), resolved))
+        });
+        let rows = hovered
+            .into_iter()
+            .chain(pinned)
+            .map(|(pinned, target)| {
+                rect()
+                    .height(Size::px(28.0))
+                    .padding((0.0, 4.0, 0.0, 8.0))
+                    .spacing(6.0)
+                    .corner_radius(4.0)
+                    .direction(Direction::Horizontal)
+                    .cross_align(Alignment::Center)
+                    .background(Color::from_hex(colors::BACKGROUND).unwrap().with_a(217))
+                    .child(
+                        svg(if pinned.is_some() {
+                            freya_icons::lucide::pin()
+                        } else {
+                            freya_icons::lucide::crosshair()
+                        })
+                        .width(Size::px(14.0))
+                        .height(Size::px(14.0))
+                        .color(Color::from_hex(colors::TEXT).unwrap()),
+                    )
+                    .child(
+                        label()
+                            .font_size(13.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::TEXT).unwrap())
+                            .max_lines(1)
+                            .text(describe(&target, me, map_size)),
+                    )
+                    .maybe_child(pinned.clone().map(|pinned| {
+                        let following = followed.as_ref() == Some(&pinned);
+                        Button::new()
+                            .height(Size::px(22.0))
+                            .padding(3.0)
+                            .icon(freya_icons::lucide::locate_fixed())
+                            .on_press(move |_| {
+                                follow(radio, (!following).then(|| pinned.clone()));
+                            })
+                            .active(following)
+                    }))
+                    .maybe_child(pinned.map(|pinned| {
+                        Button::new()
+                            .height(Size::px(22.0))
+                            .padding(3.0)
+                            .icon(freya_icons::lucide::x())
+                            .on_press(move |_| {
+                                radio
+                                    .write()
+                                    .pinned_targets
+                                    .retain(|target| *target != pinned);
+                            })
+                    }))
+                    .into()
+            })
+            .collect::<Vec<Element>>();
+
+        // Pins a typed grid reference such as "G12" or "G12-3"
+        let pin_grid = rect()
+            .width(Size::px(180.0))
+            .spacing(4.0)
+            .direction(Direction::Horizontal)
+            .cross_align(Alignment::Center)
+            .content(Content::Flex)
+            .child(
+                Input::new(grid_text)
+                    .width(Size::flex(1.0))
+                    .placeholder("Grid"),
+            )
+            .child(
+                Button::new()
+                    .height(Size::px(28.0))
+                    .padding(5.0)
+                    .icon(freya_icons::lucide::pin())
+                    .on_press(move |_| {
+                        let Some(map_size) = radio.read().info_state.map_size else {
+                            return;
+                        };
+                        let Some(position) = GridPosition::parse(grid_text.read().trim()) else {
+                            return;
+                        };
+                        let (x, y) =
+                            MapGeometry::new(map_size as f32, 0.0).grid_to_world(&position);
+                        let target = MapTarget::Place {
+                            name: position.precise(),
+                            x,
+                            y,
+                        };
+                        let mut data = radio.write();
+                        if !data.pinned_targets.contains(&target) {
+                            data.pinned_targets.push(target);
+                        }
+                        grid_text.set(String::new());
+                    }),
+            );
+
+        rect()
+            .position(Position::new_absolute().left(8.0).top(8.0))
+            .layer(Layer::Relative(3))
+            .spacing(4.0)
+            .children(rows)
+            .child(pin_grid)
+    }
+}
+
+/// Arrows on the edge of the minimap pointing at pinned targets that are out of view.
+#[derive(PartialEq)]
+pub struct EdgeArrows {
+    pub me_steam_id: u64,
+    pub map_size: f32,
+    pub margin: f32,
+    pub pos: Readable<Point2D<f32, ()>>,
+    pub zoom: Readable<f32>,
+    pub viewport: Readable<Area>,
+    /// Map rotation in degrees, arrows are turned along with the map.
+    pub rotation: Readable<f32>,
+}
+
+impl Component for EdgeArrows {
+    fn render(&self) -> impl IntoElement {
+        let radio = use_radio::<Data, DataChannel>(DataChannel::TargetsUpdate);
+
+        let data = radio.read();
+        subscribe(radio, self.me_steam_id, data.pinned_targets.iter());
+        let me = me_position(&data, self.me_steam_id);
+        let viewport = *self.viewport.read();
+        let pos = *self.pos.read();
+        let zoom = *self.zoom.read();
+        let (sin, cos) = (-*self.rotation.read()).to_radians().sin_cos();
+
+        let center = Point2D::new(viewport.width() / 2.0, viewport.height() / 2.0);
+        // Keeps arrows inside both round and square minimaps
+        let radius = viewport.width().min(viewport.height()) / 2.0 - 16.0;
+        let scale_offset = self.map_size * (zoom - 1.0) / 2.0;
+
+        let arrows = data
+            .pinned_targets
+            .iter()
+            .filter_map(|target| resolve(&data, target))
+            .filter_map(|target| {
+                let screen = Point2D::new(
+                    center.x + pos.x + (target.x + self.margin) * zoom - scale_offset,
+                    center.y + pos.y + (self.map_size - target.y - self.margin) * zoom
+                        - scale_offset,
+                );
+                // The map turns around the center, so the offset turns with it
+                let offset = screen - center;
+                let offset = Vector2D::new(
+                    offset.x * cos - offset.y * sin,
+                    offset.x * sin + offset.y * cos,
+                );
+                if offset.length() <= radius {
+                    return None;
+                }
+
+                let edge = center + offset.normalize() * radius;
+                let angle = offset.x.atan2(-offset.y).to_degrees();
+                Some(
+                    rect()
+                        .position(
+                            Position::new_absolute()
+                                .left(edge.x - 12.0)
+                                .top(edge.y - 12.0),
+                        )
+                        .width(Size::px(24.0))
+                        .height(Size::px(24.0))
+                        .center()
+                        .child(
+                            svg(freya_icons::lucide::navigation_2())
+                                .width(Size::px(14.0))
+                                .height(Size::px(14.0))
+                                .rotate(angle)
+                                .color(Color::from_hex("#f3c86d").unwrap())
+                                .stroke(Color::BLACK),
+                        )
+                        .maybe_child(me.map(|me| {
+                            rect()
+                                .position(Position::new_absolute().top(20.0))
+                                .padding((1.0, 3.0))
+                                .corner_radius(2.0)
+                                .background(Color::from_hex("#0000008f").unwrap())
+                                .child(
+                                    label()
+                                        .font_size(9.0)
+                                        .font_weight(FontWeight::BOLD)
+                                        .color(Color::WHITE)
+                                        .max_lines(1)
+                                        .text(format_distance(distance(me, (target.x, target.y)))),
+                                )
+                        }))
+                        .into(),
+                )
+            })
+            .collect::<Vec<Element>>();
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::Fill)
+            .position(Position::new_absolute())
+            .layer(Layer::Relative(3))
+            .children(arrows)
+    }
+}
@@ -0,0 +1,94 @@
+use freya::prelude::*;
+
+use crate::{
+    components::markers::{Align, base_marker, line_segment},
+    utils::{AnnotationShape, index_to_color, index_to_icon},
+};
+
+/// Icon and label of an annotation, placed at the shape's anchor.
+pub fn annotation_pin(
+    shape: &AnnotationShape,
+    icon: i32,
+    color: i32,
+    text: String,
+    margin: f32,
+    map_size: f32,
+) -> Rect {
+    let (color, color_muted) = index_to_color(color);
+    let (x, y) = shape.anchor();
+    let is_pin = matches!(shape, AnnotationShape::Pin { .. });
+
+    base_marker(
+        x,
+        y,
+        if is_pin { 20.0 } else { 8.0 },
+        margin,
+        map_size,
+        if is_pin { Align::Bottom } else { Align::Center },
+    )
+    .main_align(Alignment::Center)
+    .cross_align(Alignment::Center)
+    .child(if is_pin {
+        rect()
+            .position(Position::new_absolute())
+            .width(Size::px(20.0))
+            .height(Size::px(20.0))
+            .corner_radius(CornerRadius::new_all(1000.0))
+            .background(color_muted)
+            .border(Border::new().width(2.0).fill(color))
+            .center()
+            .child(
+                svg(index_to_icon(icon))
+                    .width(Size::px(9.0))
+                    .height(Size::px(9.0))
+                    .color(color),
+            )
+            .into_element()
+    } else {
+        rect()
+            .position(Position::new_absolute())
+            .width(Size::px(8.0))
+            .height(Size::px(8.0))
+            .corner_radius(CornerRadius::new_all(1000.0))
+            .background(color)
+            .border(Border::new().width(1.0).fill(Color::BLACK))
+            .into_element()
+    })
+    .maybe_child((!text.is_empty()).then(|| {
+        rect().offset_y(if is_pin { 18.0 } else { 8.0 }).child(
+            rect()
+                .background(Color::from_hex("#0000004f").unwrap())
+                .padding(2.0)
+                .center()
+                .child(
+                    label()
+                        .font_family("Roboto Condensed")
+                        .font_weight(FontWeight::BLACK)
+                        .color(color)
+                        .font_size(12.0)
+                        .max_lines(1)
+                        .text(text),
+                ),
+        )
+    }))
+}
+
+/// Outline of a line or polygon annotation.
+pub fn annotation_outline(
+    shape: &AnnotationShape,
+    color: i32,
+    margin: f32,
+    map_size: f32,
+) -> Vec<Element> {
+    let (color, _) = index_to_color(color);
+
+    shape
+        .segments()
+        .into_iter()
+        .map(|(from, to)| {
+            line_segment(from, to, 3.0, margin, map_size)
+                .background(color)
+                .into()
+        })
+        .collect()
+}
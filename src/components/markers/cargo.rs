@@ -1,30 +1,57 @@
 use freya::prelude::*;
 use rustplus_rs::AppMarker;
 
-use crate::components::markers::{Align, base_marker};
+use crate::{
+    components::{
+        markers::{Align, base_marker, use_marker},
+        use_interpolated,
+    },
+    utils::Pose,
+};
 
 #[derive(PartialEq)]
 pub struct CargoShip {
-    pub marker: AppMarker,
+    pub marker_id: u32,
     pub map_size: f32,
     pub margin: f32,
+    pub marker: Option<AppMarker>,
 }
 
 impl CargoShip {
-    pub fn new(marker: AppMarker, map_size: f32, margin: f32) -> Self {
+    pub fn new(marker_id: u32, map_size: f32, margin: f32) -> Self {
         Self {
-            marker,
+            marker_id,
             map_size,
             margin,
+            marker: None,
         }
     }
+
+    /// Draws the given marker instead of looking it up in the live markers.
+    pub fn marker(mut self, marker: Option<AppMarker>) -> Self {
+        self.marker = marker;
+        self
+    }
 }
 
 impl Component for CargoShip {
     fn render(&self) -> impl IntoElement {
+        let marker = use_marker(self.marker_id, &self.marker);
+        let pose = use_interpolated(
+            marker
+                .as_ref()
+                .map(|marker| Pose::new(marker.x, marker.y).rotation(marker.rotation))
+                .unwrap_or_default(),
+            self.marker.is_some(),
+        );
+
+        if marker.is_none() {
+            return rect().into();
+        }
+
         base_marker(
-            self.marker.x,
-            self.marker.y,
+            pose.x,
+            pose.y,
             4.0,
             self.margin,
             self.map_size,
@@ -39,7 +66,7 @@ impl Component for CargoShip {
             )))
             .width(Size::px(50.))
             .height(Size::px(50.))
-            .rotate(self.marker.rotation),
+            .rotate(pose.rotation),
         )
     }
 }
@@ -0,0 +1,26 @@
+use freya::prelude::*;
+
+use crate::components::markers::{Align, base_marker};
+
+/// Badge standing in for several markers that would overlap at the current zoom.
+pub fn cluster_badge(x: f32, y: f32, count: usize, margin: f32, map_size: f32) -> Rect {
+    base_marker(x, y, 30.0, margin, map_size, Align::Center)
+        .corner_radius(CornerRadius::new_all(1000.0))
+        .background(Color::from_hex("#96d32c").unwrap())
+        .border(
+            Border::new()
+                .width(2.0)
+                .fill(Color::from_hex("#000000DC").unwrap()),
+        )
+        .main_align(Alignment::Center)
+        .cross_align(Alignment::Center)
+        .child(
+            label()
+                .font_family("Roboto Condensed")
+                .font_weight(FontWeight::BLACK)
+                .font_size(14.0)
+                .color(Color::from_hex("#000000DC").unwrap())
+                .max_lines(1)
+                .text(count.to_string()),
+        )
+}
@@ -0,0 +1,78 @@
+use freya::prelude::*;
+use rustplus_rs::AppMarker;
+
+use crate::{
+    components::{
+        markers::{Align, base_marker, use_marker},
+        use_interpolated,
+    },
+    utils::Pose,
+};
+
+/// Moving markers without a dedicated icon, such as helicopters, drawn as a labelled dot.
+#[derive(PartialEq)]
+pub struct EventMarker {
+    pub marker_id: u32,
+    pub map_size: f32,
+    pub margin: f32,
+    pub zoom: Readable<f32>,
+    pub marker: Option<AppMarker>,
+}
+
+impl EventMarker {
+    pub fn new(marker_id: u32, map_size: f32, margin: f32, zoom: Readable<f32>) -> Self {
+        Self {
+            marker_id,
+            map_size,
+            margin,
+            zoom,
+            marker: None,
+        }
+    }
+
+    /// Draws the given marker instead of looking it up in the live markers.
+    pub fn marker(mut self, marker: Option<AppMarker>) -> Self {
+        self.marker = marker;
+        self
+    }
+}
+
+impl Component for EventMarker {
+    fn render(&self) -> impl IntoElement {
+        let marker = use_marker(self.marker_id, &self.marker);
+        let pose = use_interpolated(
+            marker
+                .as_ref()
+                .map(|marker| Pose::new(marker.x, marker.y))
+                .unwrap_or_default(),
+            self.marker.is_some(),
+        );
+
+        let Some(marker) = marker else {
+            return rect().into();
+        };
+
+        base_marker(
+            pose.x,
+            pose.y,
+            4.0,
+            self.margin,
+            self.map_size,
+            Align::Center,
+        )
+        .corner_radius(CornerRadius::new_all(1000.0))
+        .background(Color::YELLOW)
+        .main_align(Alignment::Center)
+        .cross_align(Alignment::Center)
+        .child(
+            label()
+                .width(Size::px(500.0))
+                .text_align(TextAlign::Center)
+                // Magic numbers :)
+                .font_size(8.864 / *self.zoom.read() + 2.446)
+                .font_family("PermanentMarker")
+                .color(Color::from_hex("#191919e6").unwrap())
+                .text(format!("{:?}", marker.r#type())),
+        )
+    }
+}
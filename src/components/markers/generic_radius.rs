@@ -0,0 +1,69 @@
+use freya::prelude::*;
+use rustplus_rs::AppMarker;
+
+use crate::components::markers::{Align, base_marker, use_marker};
+
+#[derive(PartialEq)]
+pub struct GenericRadius {
+    pub marker_id: u32,
+    pub map_size: f32,
+    pub margin: f32,
+    pub marker: Option<AppMarker>,
+}
+
+impl GenericRadius {
+    pub fn new(marker_id: u32, map_size: f32, margin: f32) -> Self {
+        Self {
+            marker_id,
+            map_size,
+            margin,
+            marker: None,
+        }
+    }
+
+    /// Draws the given marker instead of looking it up in the live markers.
+    pub fn marker(mut self, marker: Option<AppMarker>) -> Self {
+        self.marker = marker;
+        self
+    }
+}
+
+fn vector_to_color(r: f32, g: f32, b: f32, a: f32) -> Color {
+    Color::from_argb(
+        (a.clamp(0.0, 1.0) * 255.0) as u8,
+        (r.clamp(0.0, 1.0) * 255.0) as u8,
+        (g.clamp(0.0, 1.0) * 255.0) as u8,
+        (b.clamp(0.0, 1.0) * 255.0) as u8,
+    )
+}
+
+impl Component for GenericRadius {
+    fn render(&self) -> impl IntoElement {
+        let Some(marker) = use_marker(self.marker_id, &self.marker) else {
+            return rect().into();
+        };
+
+        // The server sends the fill as color1 and the outline as color2,
+        // with alpha applying to the whole zone.
+        let fill = match &marker.color1 {
+            Some(color) => vector_to_color(color.x, color.y, color.z, color.w * marker.alpha),
+            None => Color::from_hex("#c0393966").unwrap(),
+        };
+        let outline = match &marker.color2 {
+            Some(color) => vector_to_color(color.x, color.y, color.z, color.w),
+            None => Color::from_hex("#c03939").unwrap(),
+        };
+
+        base_marker(
+            marker.x,
+            marker.y,
+            marker.radius * 2.0,
+            self.margin,
+            self.map_size,
+            Align::Center,
+        )
+        .corner_radius(CornerRadius::new_all(1000.0))
+        .background(fill)
+        .border(Border::new().width(1.5).fill(outline))
+    }
+}
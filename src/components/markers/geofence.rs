@@ -0,0 +1,52 @@
+use freya::prelude::*;
+
+use crate::{
+    components::markers::{Align, base_area, base_marker},
+    utils::GeofenceShape,
+};
+
+/// Outline of a geofence, drawn without a label while it is still being drawn.
+pub fn geofence_zone(
+    shape: GeofenceShape,
+    name: Option<String>,
+    margin: f32,
+    map_size: f32,
+) -> Rect {
+    let zone = match shape {
+        GeofenceShape::Circle { x, y, radius } => {
+            base_marker(x, y, radius * 2.0, margin, map_size, Align::Center)
+                .corner_radius(CornerRadius::new_all(1000.0))
+        }
+        GeofenceShape::Rectangle {
+            left,
+            bottom,
+            right,
+            top,
+        } => base_area(
+            (left + right) / 2.0,
+            (bottom + top) / 2.0,
+            right - left,
+            top - bottom,
+            margin,
+            map_size,
+            Align::Center,
+        ),
+    };
+
+    zone.background(Color::from_hex("#f3c86d33").unwrap())
+        .border(
+            Border::new()
+                .width(1.5)
+                .fill(Color::from_hex("#f3c86d").unwrap()),
+        )
+        .main_align(Alignment::Center)
+        .cross_align(Alignment::Center)
+        .maybe_child(name.map(|name| {
+            label()
+                .font_family("PermanentMarker")
+                .font_size(8.0)
+                .color(Color::from_hex("#f3c86d").unwrap())
+                .max_lines(1)
+                .text(name)
+        }))
+}
@@ -0,0 +1,26 @@
+use freya::prelude::*;
+
+use crate::components::markers::{Align, base_marker};
+
+/// Size of the square world-space bins deaths are counted in.
+pub const HEATMAP_CELL_SIZE: f32 = 50.0;
+
+/// A blurred-looking spot for one heatmap bin, going from yellow to red as `intensity` nears 1.
+pub fn heat_spot(x: f32, y: f32, intensity: f32, margin: f32, map_size: f32) -> Rect {
+    let intensity = intensity.clamp(0.0, 1.0);
+    let size = HEATMAP_CELL_SIZE * (1.5 + intensity);
+
+    base_marker(x, y, size, margin, map_size, Align::Center)
+        .corner_radius(CornerRadius::new_all(1000.0))
+        .background(Color::from_argb(
+            (70.0 + 130.0 * intensity) as u8,
+            255,
+            (220.0 * (1.0 - intensity)) as u8,
+            0,
+        ))
+        .shadow(
+            Shadow::new()
+                .blur(size / 2.0)
+                .color(Color::from_argb((120.0 * intensity) as u8, 255, 0, 0)),
+        )
+}
@@ -0,0 +1,32 @@
+use freya::prelude::*;
+
+use crate::{
+    components::markers::{Align, base_marker},
+    utils::format_distance,
+};
+
+/// Route point of the measuring tool, labelled with the distance travelled so far.
+pub fn measure_point(point: (f32, f32), total: Option<f32>, margin: f32, map_size: f32) -> Rect {
+    base_marker(point.0, point.1, 8.0, margin, map_size, Align::Center)
+        .corner_radius(CornerRadius::new_all(1000.0))
+        .background(Color::WHITE)
+        .border(Border::new().width(1.0).fill(Color::BLACK))
+        .main_align(Alignment::Center)
+        .cross_align(Alignment::Center)
+        .maybe_child(total.map(|total| {
+            rect().offset_y(12.0).child(
+                rect()
+                    .background(Color::from_hex("#0000004f").unwrap())
+                    .padding(2.0)
+                    .child(
+                        label()
+                            .font_family("Roboto Condensed")
+                            .font_weight(FontWeight::BLACK)
+                            .color(Color::WHITE)
+                            .font_size(12.0)
+                            .max_lines(1)
+                            .text(format_distance(total)),
+                    ),
+            )
+        }))
+}
@@ -1,4 +1,7 @@
-use freya::prelude::*;
+use freya::{prelude::*, radio::use_radio};
+use rustplus_rs::AppMarker;
+
+use crate::{Data, DataChannel};
 
 mod player;
 pub use player::*;
@@ -12,6 +15,33 @@ mod vending;
 pub use vending::*;
 mod cargo;
 pub use cargo::*;
+mod generic_radius;
+pub use generic_radius::*;
+mod trail;
+pub use trail::*;
+mod heatmap;
+pub use heatmap::*;
+mod geofence;
+pub use geofence::*;
+mod annotation;
+pub use annotation::*;
+mod measure;
+pub use measure::*;
+mod cluster;
+pub use cluster::*;
+mod event;
+pub use event::*;
+
+/// Marker as of its latest `MarkerUpdate`, or `snapshot` when drawing recorded history.
+/// Only this marker's channel is subscribed, so other markers moving don't re-render it.
+pub fn use_marker(marker_id: u32, snapshot: &Option<AppMarker>) -> Option<AppMarker> {
+    let radio = use_radio::<Data, DataChannel>(DataChannel::MarkerUpdate(marker_id));
+    let markers = radio.slice_current(|s| &s.map_markers.markers);
+    match snapshot {
+        Some(marker) => Some(marker.clone()),
+        None => markers.read().get(&marker_id).cloned(),
+    }
+}
 
 #[derive(PartialEq)]
 pub enum Align {
@@ -20,17 +50,59 @@ pub enum Align {
 }
 
 pub fn base_marker(x: f32, y: f32, size: f32, margin: f32, map_size: f32, align: Align) -> Rect {
+    base_area(x, y, size, size, margin, map_size, align)
+}
+
+/// Like [`base_marker`] for areas that aren't square.
+pub fn base_area(
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    margin: f32,
+    map_size: f32,
+    align: Align,
+) -> Rect {
     rect()
-        .width(Size::px(size))
-        .height(Size::px(size))
-        .position(Position::new_absolute().left(x - size / 2.0 + margin).top(
+        .width(Size::px(width))
+        .height(Size::px(height))
+        .position(Position::new_absolute().left(x - width / 2.0 + margin).top(
             map_size
                 - y
                 - if align == Align::Bottom {
-                    size
+                    height
                 } else {
-                    size / 2.0
+                    height / 2.0
                 }
                 - margin,
         ))
 }
+
+/// A straight bar between two world points, rotated into place.
+pub fn line_segment(
+    from: (f32, f32),
+    to: (f32, f32),
+    thickness: f32,
+    margin: f32,
+    map_size: f32,
+) -> Rect {
+    let dx = to.0 - from.0;
+    let dy = to.1 - from.1;
+    let length = (dx * dx + dy * dy).sqrt();
+    // Screen space has y pointing down while world space has it pointing up
+    let angle = (-dy).atan2(dx).to_degrees();
+
+    let mid_x = (from.0 + to.0) / 2.0;
+    let mid_y = (from.1 + to.1) / 2.0;
+
+    rect()
+        .width(Size::px(length))
+        .height(Size::px(thickness))
+        .position(
+            Position::new_absolute()
+                .left(mid_x - length / 2.0 + margin)
+                .top(map_size - mid_y - thickness / 2.0 - margin),
+        )
+        .rotate(angle)
+        .corner_radius(CornerRadius::new_all(1000.0))
+}
@@ -1,8 +1,12 @@
 use freya::{prelude::*, radio::use_radio};
 
 use crate::{
-    DataChannel, colors,
-    components::markers::{Align, base_marker},
+    DataChannel, TeamMember, colors,
+    components::{
+        markers::{Align, base_marker},
+        use_interpolated,
+    },
+    utils::Pose,
 };
 
 #[derive(PartialEq)]
@@ -10,6 +14,7 @@ pub struct Player {
     pub member_id: u64,
     pub map_size: f32,
     pub margin: f32,
+    pub member: Option<TeamMember>,
 }
 
 impl Player {
@@ -18,8 +23,15 @@ impl Player {
             member_id: member_id,
             map_size,
             margin,
+            member: None,
         }
     }
+
+    /// Draws the given member instead of looking it up in the live team info.
+    pub fn member(mut self, member: Option<TeamMember>) -> Self {
+        self.member = member;
+        self
+    }
 }
 
 impl Component for Player {
@@ -28,9 +40,20 @@ impl Component for Player {
             crate::DataChannel::TeamMemberUpdate(self.member_id),
         );
         let members = radio.slice_current(|s| &s.team_info.members);
-        let member = members.read().get(&self.member_id).cloned();
+        let member = match &self.member {
+            Some(member) => Some(member.clone()),
+            None => members.read().get(&self.member_id).cloned(),
+        };
 
         let user_data = radio.slice(DataChannel::UserDataUpdate, |s| &s.user_data);
+        // Recorded snapshots are scrubbed through, so they jump instead of gliding
+        let pose = use_interpolated(
+            member
+                .as_ref()
+                .map(|member| Pose::new(member.x, member.y))
+                .unwrap_or_default(),
+            self.member.is_some(),
+        );
 
         if member.is_none() {
             return rect().into();
@@ -48,8 +71,8 @@ impl Component for Player {
 
         if me {
             base_marker(
-                member.x,
-                member.y,
+                pose.x,
+                pose.y,
                 12.0,
                 self.margin,
                 self.map_size,
@@ -73,8 +96,8 @@ impl Component for Player {
             .cross_align(Alignment::Center)
         } else {
             base_marker(
-                member.x,
-                member.y,
+                pose.x,
+                pose.y,
                 9.0,
                 self.margin,
                 self.map_size,
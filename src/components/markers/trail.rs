@@ -0,0 +1,17 @@
+use freya::prelude::*;
+
+use crate::{components::markers::line_segment, utils::TrailPoint};
+
+/// A single straight piece of a trail polyline, drawn as a rotated bar between two points.
+pub fn trail_segment(
+    from: TrailPoint,
+    to: TrailPoint,
+    color: Color,
+    opacity: f32,
+    margin: f32,
+    map_size: f32,
+) -> Rect {
+    line_segment((from.x, from.y), (to.x, to.y), 2.0, margin, map_size)
+        .opacity(opacity)
+        .background(color)
+}
@@ -1,30 +1,42 @@
 use freya::prelude::*;
 use rustplus_rs::AppMarker;
 
-use crate::components::markers::{Align, base_marker};
+use crate::components::markers::{Align, base_marker, use_marker};
 
 #[derive(PartialEq)]
 pub struct VendingMachine {
-    pub marker: AppMarker,
+    pub marker_id: u32,
     pub map_size: f32,
     pub margin: f32,
+    pub marker: Option<AppMarker>,
 }
 
 impl VendingMachine {
-    pub fn new(marker: AppMarker, map_size: f32, margin: f32) -> Self {
+    pub fn new(marker_id: u32, map_size: f32, margin: f32) -> Self {
         Self {
-            marker,
+            marker_id,
             map_size,
             margin,
+            marker: None,
         }
     }
+
+    /// Draws the given marker instead of looking it up in the live markers.
+    pub fn marker(mut self, marker: Option<AppMarker>) -> Self {
+        self.marker = marker;
+        self
+    }
 }
 
 impl Component for VendingMachine {
     fn render(&self) -> impl IntoElement {
+        let Some(marker) = use_marker(self.marker_id, &self.marker) else {
+            return rect().into();
+        };
+
         base_marker(
-            self.marker.x,
-            self.marker.y,
+            marker.x,
+            marker.y,
             26.0,
             self.margin,
             self.map_size,
@@ -32,7 +44,7 @@ impl Component for VendingMachine {
         )
         .corner_radius(CornerRadius::new_all(1000.0))
         .background(
-            Color::from_hex(if self.marker.out_of_stock {
+            Color::from_hex(if marker.out_of_stock {
                 "#d36516"
             } else {
                 "#96d32c"
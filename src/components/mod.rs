@@ -29,3 +29,13 @@ mod toast;
 pub use toast::*;
 mod modal;
 pub use modal::*;
+mod population_chart;
+pub use population_chart::*;
+mod event_timers;
+pub use event_timers::*;
+mod map_targets;
+pub use map_targets::*;
+mod monument_panel;
+pub use monument_panel::*;
+mod interpolated;
+pub use interpolated::*;
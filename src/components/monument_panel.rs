@@ -0,0 +1,150 @@
+use freya::{prelude::*, radio::use_radio};
+
+use crate::{
+    Data, DataChannel, colors,
+    components::Button,
+    utils::{MapTarget, grid_reference, monument_info, normalize_monument_name},
+};
+
+fn info_row(title: &'static str, value: String) -> Element {
+    rect()
+        .width(Size::Fill)
+        .direction(Direction::Horizontal)
+        .main_align(Alignment::SpaceBetween)
+        .child(
+            label()
+                .font_size(12.0)
+                .color(Color::from_hex(colors::ICON).unwrap())
+                .text(title),
+        )
+        .child(
+            label()
+                .font_size(13.0)
+                .font_weight(FontWeight::BOLD)
+                .color(Color::from_hex(colors::TEXT).unwrap())
+                .text(value),
+        )
+        .into()
+}
+
+fn list_or_none(values: &[String]) -> String {
+    if values.is_empty() {
+        "None".to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+/// Puzzle, radiation and loot details of the monument clicked on the main map.
+#[derive(PartialEq)]
+pub struct MonumentPanel {}
+
+impl Component for MonumentPanel {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::MonumentSelectUpdate);
+        let pinned_targets = radio.slice(DataChannel::TargetsUpdate, |s| &s.pinned_targets);
+
+        let Some(monument) = radio.read().selected_monument.clone() else {
+            return rect().into_element();
+        };
+        let map_size = radio.read().info_state.map_size;
+        let name = normalize_monument_name(monument.token.clone());
+        let grid = map_size
+            .map(|map_size| grid_reference(monument.x, monument.y, map_size as f32))
+            .unwrap_or_else(|| "--".to_string());
+
+        let details: Vec<Element> = match monument_info(&monument.token) {
+            Some(info) => vec![
+                info_row("RADIATION", info.radiation.clone()),
+                info_row("KEYCARDS", list_or_none(&info.keycards)),
+                info_row(
+                    "FUSE",
+                    if info.fuse { "Required" } else { "No" }.to_string(),
+                ),
+                info_row(
+                    "RECYCLER",
+                    if info.recycler { "Yes" } else { "No" }.to_string(),
+                ),
+                info_row(
+                    "WORKBENCH",
+                    info.workbench
+                        .map(|level| format!("Level {}", level))
+                        .unwrap_or_else(|| "None".to_string()),
+                ),
+                info_row(
+                    "SAFE ZONE",
+                    if info.safe_zone { "Yes" } else { "No" }.to_string(),
+                ),
+                info_row("CRATES", list_or_none(&info.crates)),
+            ],
+            None => vec![
+                label()
+                    .font_size(12.0)
+                    .color(Color::from_hex(colors::ICON).unwrap())
+                    .text("No details known for this monument")
+                    .into(),
+            ],
+        };
+
+        let target = MapTarget::Place {
+            name: name.clone(),
+            x: monument.x,
+            y: monument.y,
+        };
+        let pinned = pinned_targets.read().contains(&target);
+
+        rect()
+            .position(Position::new_absolute().right(8.0).top(8.0))
+            .layer(Layer::Relative(3))
+            .width(Size::px(260.0))
+            .padding(12.0)
+            .spacing(6.0)
+            .corner_radius(8.0)
+            .background(Color::from_hex(colors::BACKGROUND).unwrap().with_a(230))
+            .child(
+                rect()
+                    .width(Size::Fill)
+                    .direction(Direction::Horizontal)
+                    .cross_align(Alignment::Center)
+                    .spacing(4.0)
+                    .content(Content::Flex)
+                    .child(
+                        label()
+                            .width(Size::flex(1.0))
+                            .font_size(15.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::TEXT).unwrap())
+                            .max_lines(1)
+                            .text(format!("{}  {}", name, grid)),
+                    )
+                    .child(
+                        Button::new()
+                            .height(Size::px(22.0))
+                            .padding(3.0)
+                            .icon(freya_icons::lucide::pin())
+                            .on_press(move |_| {
+                                let mut data = radio.write_channel(DataChannel::TargetsUpdate);
+                                if pinned {
+                                    data.pinned_targets.retain(|pinned| *pinned != target);
+                                } else {
+                                    data.pinned_targets.push(target.clone());
+                                }
+                            })
+                            .active(pinned),
+                    )
+                    .child(
+                        Button::new()
+                            .height(Size::px(22.0))
+                            .padding(3.0)
+                            .icon(freya_icons::lucide::x())
+                            .on_press(move |_| {
+                                radio
+                                    .write_channel(DataChannel::MonumentSelectUpdate)
+                                    .selected_monument = None;
+                            }),
+                    ),
+            )
+            .children(details)
+            .into_element()
+    }
+}
@@ -1,6 +1,14 @@
 use freya::{prelude::*, radio::use_radio};
 
-use crate::{Data, DataChannel, colors, components::CachedImage};
+use freya_router::prelude::RouterContext;
+
+use crate::{
+    Data, DataChannel,
+    app::Route,
+    colors,
+    components::{Button, CachedImage, follow},
+    utils::MapTarget,
+};
 
 #[derive(PartialEq)]
 pub struct PlayerCard {
@@ -23,6 +31,9 @@ impl Component for PlayerCard {
     fn render(&self) -> impl IntoElement {
         let radio = use_radio::<Data, DataChannel>(DataChannel::TeamMemberUpdate(self.steam_id));
         let steam_profile = radio.read().team_info.members.get(&self.steam_id).cloned();
+        let follow_target = radio.slice(DataChannel::FollowUpdate, |s| &s.follow_target);
+        let following = *follow_target.read() == Some(MapTarget::Member(self.steam_id));
+        let steam_id = self.steam_id;
 
         rect()
             .width(Size::Fill)
@@ -31,6 +42,7 @@ impl Component for PlayerCard {
             .padding(8.0)
             .spacing(4.0)
             .cross_align(Alignment::Center)
+            .content(Content::Flex)
             .background(Color::from_hex("#181818").unwrap())
             .corner_radius(CornerRadius::new_all(8.0))
             .children([
@@ -102,6 +114,16 @@ impl Component for PlayerCard {
                             .into(),
                     ])
                     .into(),
+                rect().width(Size::flex(1.0)).into(),
+                Button::new()
+                    .height(Size::Fill)
+                    .icon(freya_icons::lucide::locate_fixed())
+                    .on_press(move |_| {
+                        follow(radio, Some(MapTarget::Member(steam_id)));
+                        RouterContext::get().replace(Route::Map);
+                    })
+                    .active(following)
+                    .into(),
             ])
     }
 }
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Local};
+use freya::prelude::*;
+
+use crate::{colors, utils::PopulationSample};
+
+const BUCKETS: i64 = 48;
+
+#[derive(PartialEq)]
+pub struct PopulationChart {
+    samples: Readable<Vec<PopulationSample>>,
+    range: i64,
+}
+
+impl PopulationChart {
+    pub fn new(samples: impl Into<Readable<Vec<PopulationSample>>>) -> Self {
+        Self {
+            samples: samples.into(),
+            range: 24 * 60 * 60,
+        }
+    }
+
+    /// How many seconds back from now the chart covers.
+    pub fn range(mut self, range: i64) -> Self {
+        self.range = range;
+        self
+    }
+}
+
+impl Component for PopulationChart {
+    fn render(&self) -> impl IntoElement {
+        let now = chrono::Utc::now().timestamp();
+        let start = now - self.range;
+        let bucket_size = (self.range / BUCKETS).max(1);
+
+        // (players, queued, sample count) per bucket, averaged below
+        let mut buckets = vec![(0u32, 0u32, 0u32); BUCKETS as usize];
+        let mut max = 1;
+        for sample in self.samples.read().iter() {
+            if sample.timestamp < start || sample.timestamp > now {
+                continue;
+            }
+            let index = (((sample.timestamp - start) / bucket_size) as usize).min(buckets.len() - 1);
+            buckets[index].0 += sample.players;
+            buckets[index].1 += sample.queued_players;
+            buckets[index].2 += 1;
+            max = max
+                .max(sample.max_players)
+                .max(sample.players + sample.queued_players);
+        }
+
+        let time_label = |timestamp: i64| match DateTime::from_timestamp(timestamp, 0) {
+            Some(time) => time
+                .with_timezone(&Local)
+                .format(if self.range > 24 * 60 * 60 {
+                    "%a %H:%M"
+                } else {
+                    "%H:%M"
+                })
+                .to_string(),
+            None => String::new(),
+        };
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::Fill)
+            .spacing(4.0)
+            .content(Content::Flex)
+            .children([
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::flex(1.0))
+                    .spacing(1.0)
+                    .direction(Direction::Horizontal)
+                    .content(Content::Flex)
+                    .children(
+                        buckets
+                            .into_iter()
+                            .map(|(players, queued, count)| {
+                                let (players, queued) = if count > 0 {
+                                    (players as f32 / count as f32, queued as f32 / count as f32)
+                                } else {
+                                    (0.0, 0.0)
+                                };
+
+                                rect()
+                                    .width(Size::flex(1.0))
+                                    .height(Size::Fill)
+                                    .main_align(Alignment::End)
+                                    .background(Color::from_hex(colors::BACKGROUND_DARK).unwrap())
+                                    .children([
+                                        rect()
+                                            .width(Size::Fill)
+                                            .height(Size::percent(queued / max as f32 * 100.0))
+                                            .background(Color::from_hex(colors::ACCENT).unwrap())
+                                            .into(),
+                                        rect()
+                                            .width(Size::Fill)
+                                            .height(Size::percent(players / max as f32 * 100.0))
+                                            .background(Color::from_hex(colors::SELECT).unwrap())
+                                            .into(),
+                                    ])
+                                    .into()
+                            })
+                            .collect::<Vec<Element>>(),
+                    )
+                    .into(),
+                rect()
+                    .width(Size::Fill)
+                    .direction(Direction::Horizontal)
+                    .main_align(Alignment::SpaceBetween)
+                    .children([
+                        label()
+                            .font_size(12.0)
+                            .color(Color::from_hex(colors::ICON).unwrap())
+                            .text(time_label(start))
+                            .into(),
+                        label()
+                            .font_size(12.0)
+                            .color(Color::from_hex(colors::ICON).unwrap())
+                            .text(format!("MAX {}", max))
+                            .into(),
+                        label()
+                            .font_size(12.0)
+                            .color(Color::from_hex(colors::ICON).unwrap())
+                            .text(time_label(now))
+                            .into(),
+                    ])
+                    .into(),
+            ])
+    }
+}
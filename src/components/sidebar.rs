@@ -48,6 +48,28 @@ impl Component for Sidebar {
                     })
                     .active(RouterContext::get().full_route_string().starts_with("/map"))
                     .into(),
+                Button::new()
+                    .width(Size::Fill)
+                    .height(Size::px(40.0))
+                    .icon(freya_icons::lucide::history())
+                    .text("TIMELINE")
+                    .on_press(move |_| {
+                        RouterContext::get().replace(Route::Timeline);
+                    })
+                    .active(RouterContext::get().current::<Route>() == Route::Timeline)
+                    .into(),
+                Button::new()
+                    .width(Size::Fill)
+                    .height(Size::px(40.0))
+                    .icon(freya_icons::lucide::bell())
+                    .text("NOTIFICATIONS")
+                    .on_press(move |_| {
+                        RouterContext::get().replace(Route::NotificationSettingsPage);
+                    })
+                    .active(
+                        RouterContext::get().current::<Route>() == Route::NotificationSettingsPage,
+                    )
+                    .into(),
                 // Button::new()
                 //     .width(Size::Fill)
                 //     .height(Size::px(40.0))
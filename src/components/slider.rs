@@ -9,6 +9,9 @@ pub struct Slider {
     max: f32,
     step: f32,
 
+    width: Size,
+    show_value: bool,
+
     background: Color,
     background_fill: Color,
 
@@ -23,6 +26,8 @@ impl Slider {
             min: 0.0,
             max: 100.0,
             step: 1.0,
+            width: Size::px(250.0),
+            show_value: true,
             background: Color::from_hex(colors::SELECT).unwrap(),
             background_fill: Color::from_hex(colors::INPUT_BACKGROUND).unwrap(),
             on_changed: None,
@@ -49,6 +54,16 @@ impl Slider {
         self
     }
 
+    pub fn width(mut self, width: Size) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn show_value(mut self, show_value: bool) -> Self {
+        self.show_value = show_value;
+        self
+    }
+
     pub fn background(mut self, background: impl Into<Color>) -> Self {
         self.background = background.into();
         self
@@ -75,12 +90,12 @@ impl Component for Slider {
         let slider_pos = use_reactive(&((((self.value - self.min) / self.step) / steps) * 100.0));
 
         rect()
-            .width(Size::px(250.0))
+            .width(self.width.clone())
             .height(Size::Fill)
             .direction(Direction::Horizontal)
             .main_align(Alignment::Center)
             .cross_align(Alignment::Center)
-            .children([
+            .maybe_child(self.show_value.then(|| {
                 rect()
                     .width(Size::px(40.0))
                     .height(Size::Fill)
@@ -99,7 +114,8 @@ impl Component for Slider {
                                 format!("{:.0}", self.value)
                             }),
                     )
-                    .into(),
+            }))
+            .child(
                 rect()
                     .on_sized(move |e: Event<SizedEventData>| size.set(e.area))
                     .width(Size::Fill)
@@ -192,8 +208,7 @@ impl Component for Slider {
                                 }
                             }
                         }
-                    })
-                    .into(),
-            ])
+                    }),
+            )
     }
 }
@@ -1,7 +1,7 @@
 use freya::{
     animation::{AnimNum, Ease, use_animation},
     prelude::*,
-    radio::use_radio,
+    radio::{Radio, use_radio},
     router::Outlet,
 };
 use freya_router::prelude::RouterContext;
@@ -9,15 +9,89 @@ use freya_router::prelude::RouterContext;
 use crate::{
     Data, DataChannel,
     app::Route,
-    components::{Button, Timeout},
-    utils::{create_toast, save_minimap_settings},
+    colors,
+    components::{Button, Dropdown, Timeout, follow},
+    utils::{
+        EXPORT_RESOLUTIONS, MapExport, MapTarget, SpawnEvent, create_toast, export_map_image,
+        save_minimap_settings,
+    },
 };
 
+const TRAIL_DURATIONS: [f32; 4] = [5.0, 10.0, 30.0, 60.0];
+
+/// Renders the map with its overlays to a PNG picked by the user, off the UI thread.
+fn export_map(radio: Radio<Data, DataChannel>, resolution: u32) {
+    let data = radio.read();
+    let (Some(map_size), Some(image)) = (data.info_state.map_size, &data.map_state.image) else {
+        return;
+    };
+    let export = MapExport {
+        resolution,
+        map_size,
+        jpg_image: image.original().clone(),
+        ocean_margin: data.map_state.ocean_margin,
+        monuments: data.map_state.monuments.clone(),
+        notes: data.team_info.map_notes.clone(),
+        members: data.team_info.members.values().cloned().collect(),
+        markers: data.map_markers.markers.values().cloned().collect(),
+        annotations: data.annotations.clone(),
+    };
+    let filename = format!(
+        "{}_map.png",
+        data.info_state.name.clone().unwrap_or_else(|| "server".to_string())
+    );
+    drop(data);
+
+    spawn(async move {
+        let path = blocking::unblock(move || {
+            rfd::FileDialog::new()
+                .add_filter("PNG image", &["png"])
+                .set_file_name(filename)
+                .save_file()
+        })
+        .await;
+        let Some(path) = path else {
+            return;
+        };
+
+        let result = blocking::unblock(move || {
+            export_map_image(&path, &export).map_err(|err| err.to_string())
+        })
+        .await;
+
+        let toasts = radio.slice_mut(DataChannel::ToastsUpdate, |s| &mut s.toasts);
+        match result {
+            Ok(_) => create_toast(
+                toasts.into_writable(),
+                "Map exported".to_string(),
+                format!("Saved a {0}x{0} map image", resolution),
+                Timeout::Default,
+                None::<fn(())>,
+            ),
+            Err(err) => {
+                create_toast(
+                    toasts.into_writable(),
+                    "Map export error".to_string(),
+                    "Error exporting the map image".to_string(),
+                    Timeout::Default,
+                    None::<fn(())>,
+                );
+                eprintln!("Error exporting map image: {:?}", err);
+            }
+        }
+    });
+}
+
 #[derive(PartialEq)]
 pub struct MapLayout {}
 impl Component for MapLayout {
     fn render(&self) -> impl IntoElement {
         let mut radio = use_radio::<Data, DataChannel>(DataChannel::MapSettingsUpdate);
+        let team_members = radio.slice(DataChannel::TeamMembersUpdate, |s| &s.team_info.members);
+        let measure_route = radio.slice(DataChannel::MeasureUpdate, |s| &s.measure_route);
+        let follow_target = radio.slice(DataChannel::FollowUpdate, |s| &s.follow_target);
+        let marker_state = radio.slice(DataChannel::MapMarkersUpdate, |s| &s.map_markers.markers);
+        let user_data = radio.slice(DataChannel::UserDataUpdate, |s| &s.user_data);
 
         let mut animation = use_animation(|_| AnimNum::new(0., 100.).ease(Ease::InOut).time(200));
 
@@ -112,6 +186,256 @@ impl Component for MapLayout {
                                     })
                                     .active(radio.read().settings.map_settings.shops)
                                     .into(),
+                                Button::new()
+                                    .height(Size::Fill)
+                                    .icon(freya_icons::lucide::radar())
+                                    .on_press(move |_| {
+                                        let zones = radio.read().settings.map_settings.zones;
+                                        radio.write().settings.map_settings.zones = !zones;
+                                    })
+                                    .active(radio.read().settings.map_settings.zones)
+                                    .into(),
+                                Button::new()
+                                    .height(Size::Fill)
+                                    .icon(freya_icons::lucide::footprints())
+                                    .on_press(move |_| {
+                                        let trails = radio.read().settings.map_settings.trails;
+                                        radio.write().settings.map_settings.trails = !trails;
+                                    })
+                                    .active(radio.read().settings.map_settings.trails)
+                                    .into(),
+                                if radio.read().settings.map_settings.trails {
+                                    Dropdown::new()
+                                        .width(Size::px(100.0))
+                                        .height(Size::Fill)
+                                        .font_size(14.0)
+                                        .title(format!(
+                                            "{} MIN",
+                                            radio.read().settings.map_settings.trail_minutes
+                                        ))
+                                        .background(
+                                            Color::from_hex(colors::SIDEBAR_BUTTON_BACKGROUND)
+                                                .unwrap(),
+                                        )
+                                        .child(
+                                            rect()
+                                                .background(Color::from_hex("#0D0D0C").unwrap())
+                                                .spacing(2.0)
+                                                .padding(4.0)
+                                                .children(
+                                                    TRAIL_DURATIONS
+                                                        .into_iter()
+                                                        .map(|minutes| {
+                                                            Button::new()
+                                                                .width(Size::Fill)
+                                                                .height(Size::px(30.0))
+                                                                .align(Alignment::Center)
+                                                                .text(format!("{} MIN", minutes))
+                                                                .on_press(move |_| {
+                                                                    radio
+                                                                        .write()
+                                                                        .settings
+                                                                        .map_settings
+                                                                        .trail_minutes = minutes;
+                                                                })
+                                                                .active(
+                                                                    radio
+                                                                        .read()
+                                                                        .settings
+                                                                        .map_settings
+                                                                        .trail_minutes
+                                                                        == minutes,
+                                                                )
+                                                                .into()
+                                                        })
+                                                        .collect::<Vec<Element>>(),
+                                                )
+                                                .into(),
+                                        )
+                                        .into()
+                                } else {
+                                    rect().into()
+                                },
+                                Button::new()
+                                    .height(Size::Fill)
+                                    .icon(freya_icons::lucide::ruler())
+                                    .on_press(move |_| {
+                                        let measuring = radio.read().measure_route.is_some();
+                                        radio
+                                            .write_channel(DataChannel::MeasureUpdate)
+                                            .measure_route =
+                                            if measuring { None } else { Some(Vec::new()) };
+                                    })
+                                    .active(measure_route.read().is_some())
+                                    .into(),
+                                Button::new()
+                                    .height(Size::Fill)
+                                    .icon(freya_icons::lucide::pen_tool())
+                                    .on_press(move |_| {
+                                        let annotations =
+                                            radio.read().settings.map_settings.annotations;
+                                        radio.write().settings.map_settings.annotations =
+                                            !annotations;
+                                    })
+                                    .active(radio.read().settings.map_settings.annotations)
+                                    .into(),
+                                Button::new()
+                                    .height(Size::Fill)
+                                    .icon(freya_icons::lucide::shield())
+                                    .on_press(move |_| {
+                                        let geofences =
+                                            radio.read().settings.map_settings.geofences;
+                                        radio.write().settings.map_settings.geofences = !geofences;
+                                    })
+                                    .active(radio.read().settings.map_settings.geofences)
+                                    .into(),
+                                Button::new()
+                                    .height(Size::Fill)
+                                    .icon(freya_icons::lucide::flame())
+                                    .on_press(move |_| {
+                                        let heatmap = radio.read().settings.map_settings.heatmap;
+                                        radio.write().settings.map_settings.heatmap = !heatmap;
+                                    })
+                                    .active(radio.read().settings.map_settings.heatmap)
+                                    .into(),
+                                if radio.read().settings.map_settings.heatmap {
+                                    let heatmap_member =
+                                        radio.read().settings.map_settings.heatmap_member;
+                                    let mut members = team_members
+                                        .read()
+                                        .values()
+                                        .map(|member| {
+                                            (Some(member.steam_id), member.name.to_uppercase())
+                                        })
+                                        .collect::<Vec<(Option<u64>, String)>>();
+                                    members.sort_by(|a, b| a.1.cmp(&b.1));
+                                    members.insert(0, (None, "EVERYONE".to_string()));
+
+                                    Dropdown::new()
+                                        .width(Size::px(140.0))
+                                        .height(Size::Fill)
+                                        .font_size(14.0)
+                                        .title(
+                                            members
+                                                .iter()
+                                                .find(|(steam_id, _)| *steam_id == heatmap_member)
+                                                .map(|(_, name)| name.clone())
+                                                .unwrap_or_else(|| "EVERYONE".to_string()),
+                                        )
+                                        .background(
+                                            Color::from_hex(colors::SIDEBAR_BUTTON_BACKGROUND)
+                                                .unwrap(),
+                                        )
+                                        .child(
+                                            rect()
+                                                .background(Color::from_hex("#0D0D0C").unwrap())
+                                                .spacing(2.0)
+                                                .padding(4.0)
+                                                .children(
+                                                    members
+                                                        .into_iter()
+                                                        .map(|(steam_id, name)| {
+                                                            Button::new()
+                                                                .width(Size::Fill)
+                                                                .height(Size::px(30.0))
+                                                                .align(Alignment::Center)
+                                                                .text(name)
+                                                                .on_press(move |_| {
+                                                                    radio
+                                                                        .write()
+                                                                        .settings
+                                                                        .map_settings
+                                                                        .heatmap_member = steam_id;
+                                                                })
+                                                                .active(heatmap_member == steam_id)
+                                                                .into()
+                                                        })
+                                                        .collect::<Vec<Element>>(),
+                                                )
+                                                .into(),
+                                        )
+                                        .into()
+                                } else {
+                                    rect().into()
+                                },
+                                {
+                                    let me_steam_id: u64 = user_data
+                                        .read()
+                                        .steam_id
+                                        .clone()
+                                        .unwrap_or("0".to_string())
+                                        .parse()
+                                        .unwrap_or(0);
+                                    let mut targets = team_members
+                                        .read()
+                                        .values()
+                                        .filter(|member| member.steam_id != me_steam_id)
+                                        .map(|member| {
+                                            (
+                                                Some(MapTarget::Member(member.steam_id)),
+                                                member.name.to_uppercase(),
+                                            )
+                                        })
+                                        .collect::<Vec<(Option<MapTarget>, String)>>();
+                                    targets.sort_by(|a, b| a.1.cmp(&b.1));
+                                    targets.insert(0, (None, "ME".to_string()));
+                                    // Only event markers are worth following, the rest barely move
+                                    targets.extend(marker_state.read().values().filter_map(
+                                        |marker| {
+                                            SpawnEvent::from_marker_type(marker.r#type()).map(
+                                                |event| {
+                                                    (
+                                                        Some(MapTarget::Marker(marker.id)),
+                                                        event.title().to_string(),
+                                                    )
+                                                },
+                                            )
+                                        },
+                                    ));
+                                    let current = follow_target.read().clone();
+
+                                    Dropdown::new()
+                                        .width(Size::px(140.0))
+                                        .height(Size::Fill)
+                                        .font_size(14.0)
+                                        .title(
+                                            targets
+                                                .iter()
+                                                .find(|(target, _)| *target == current)
+                                                .map(|(_, name)| name.clone())
+                                                .unwrap_or_else(|| "ME".to_string()),
+                                        )
+                                        .background(
+                                            Color::from_hex(colors::SIDEBAR_BUTTON_BACKGROUND)
+                                                .unwrap(),
+                                        )
+                                        .child(
+                                            rect()
+                                                .background(Color::from_hex("#0D0D0C").unwrap())
+                                                .spacing(2.0)
+                                                .padding(4.0)
+                                                .children(
+                                                    targets
+                                                        .into_iter()
+                                                        .map(|(target, name)| {
+                                                            let active = target == current;
+                                                            Button::new()
+                                                                .width(Size::Fill)
+                                                                .height(Size::px(30.0))
+                                                                .align(Alignment::Center)
+                                                                .text(name)
+                                                                .on_press(move |_| {
+                                                                    follow(radio, target.clone());
+                                                                })
+                                                                .active(active)
+                                                                .into()
+                                                        })
+                                                        .collect::<Vec<Element>>(),
+                                                )
+                                                .into(),
+                                        )
+                                        .into()
+                                },
                                 Button::new()
                                     .height(Size::Fill)
                                     .icon(freya_icons::lucide::locate_fixed())
@@ -121,6 +445,39 @@ impl Component for MapLayout {
                                     })
                                     .active(radio.read().settings.map_settings.center)
                                     .into(),
+                                Dropdown::new()
+                                    .width(Size::px(110.0))
+                                    .height(Size::Fill)
+                                    .font_size(14.0)
+                                    .title("EXPORT".to_string())
+                                    .background(
+                                        Color::from_hex(colors::SIDEBAR_BUTTON_BACKGROUND)
+                                            .unwrap(),
+                                    )
+                                    .child(
+                                        rect()
+                                            .background(Color::from_hex("#0D0D0C").unwrap())
+                                            .spacing(2.0)
+                                            .padding(4.0)
+                                            .children(
+                                                EXPORT_RESOLUTIONS
+                                                    .into_iter()
+                                                    .map(|resolution| {
+                                                        Button::new()
+                                                            .width(Size::Fill)
+                                                            .height(Size::px(30.0))
+                                                            .align(Alignment::Center)
+                                                            .text(format!("{} PX", resolution))
+                                                            .on_press(move |_| {
+                                                                export_map(radio, resolution);
+                                                            })
+                                                            .into()
+                                                    })
+                                                    .collect::<Vec<Element>>(),
+                                            )
+                                            .into(),
+                                    )
+                                    .into(),
                             ])
                             .into(),
                         Button::new()
@@ -137,6 +494,32 @@ impl Component for MapLayout {
                                     == Route::MinimapSettingsPage,
                             )
                             .into(),
+                        Button::new()
+                            .width(Size::px(130.0))
+                            .height(Size::Fill)
+                            .align(Alignment::Center)
+                            .icon(freya_icons::lucide::shield())
+                            .text("GEOFENCES")
+                            .on_press(move |_| {
+                                RouterContext::get().replace(Route::GeofencesPage);
+                            })
+                            .active(
+                                RouterContext::get().current::<Route>() == Route::GeofencesPage,
+                            )
+                            .into(),
+                        Button::new()
+                            .width(Size::px(150.0))
+                            .height(Size::Fill)
+                            .align(Alignment::Center)
+                            .icon(freya_icons::lucide::map_pin())
+                            .text("ANNOTATIONS")
+                            .on_press(move |_| {
+                                RouterContext::get().replace(Route::AnnotationsPage);
+                            })
+                            .active(
+                                RouterContext::get().current::<Route>() == Route::AnnotationsPage,
+                            )
+                            .into(),
                         rect().width(Size::flex(1.0)).into(),
                         rect()
                             .overflow(Overflow::Clip)
@@ -3,7 +3,12 @@
     windows_subsystem = "windows"
 )]
 
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    rc::Rc,
+};
 
 use freya::{
     prelude::*,
@@ -11,15 +16,16 @@ use freya::{
     tray::{
         TrayEvent, TrayIconBuilder,
         dpi::{PhysicalPosition, PhysicalSize},
-        menu::{Menu, MenuEvent, MenuItem},
+        menu::{Menu, MenuEvent, MenuItem, Submenu},
     },
     webview::WebViewPlugin,
     winit::window::{ WindowId, WindowLevel},
 };
+use euclid::Point2D;
 use futures_lite::StreamExt;
 use rand::Rng;
 use rustplus_rs::{
-    AppInfo, AppMap, AppMapMarkers, AppMarker, AppTeamInfo,
+    AppInfo, AppMapMarkers, AppMarker, AppMarkerType, AppTeamInfo,
     app_map::Monument,
     app_team_info::{Note},
 };
@@ -32,11 +38,13 @@ mod utils;
 mod colors;
 
 use crate::{
-    components::{Modal, Timeout, Toast}, pages::{MapSettings, Minimap, MinimapSettings, UserData}, utils::{Poller, ServerData, load_minimap_settings}
+    components::{Modal, Timeout, Toast}, pages::{MapSettings, Minimap, MinimapPresets, MinimapSettings, NotificationKind, NotificationSettings, Playback, UserData}, utils::{Annotation, AnnotationDraft, Geofence, MapImage, MapTarget, GeofenceDraft, GeofenceSubject, GeofenceTracker, HISTORY_INTERVAL_SECS, HistorySnapshot, POPULATION_RETENTION_SECS, Poller, PopulationSample, ServerData, SpawnEvent, SpawnLog, TimelineEvent, TimelineEventKind, TrailPoint, append_history, append_population, archive_wipe, check_wipe, load_annotations, load_geofences, load_history, load_population, load_spawn_log, save_annotations, save_geofences, save_spawn_log, load_minimap_settings, load_minimap_presets, save_minimap_presets, apply_trail_step, trail_step, load_notification_settings, load_timeline, append_timeline}
 };
 use app::MyApp;
 
 const ICON: &[u8] = include_bytes!("./assets/oxide_plus_icon.png");
+/// Tray menu ids of minimap presets are this followed by the preset name.
+const MINIMAP_PRESET_MENU_PREFIX: &str = "minimap_preset:";
 
 fn main() {
     let mut radio_station = RadioStation::create_global(Data::default());
@@ -46,22 +54,33 @@ fn main() {
 
     radio_station.write_channel(DataChannel::NoUpdate).state_tx = Some(state_tx.clone());
 
-    let tray_icon = || {
-        let tray_menu = Menu::new();
-        let _ = tray_menu.append(&MenuItem::with_id("show", "Show", true, None));
-        let _ = tray_menu.append(&MenuItem::with_id(
-            "toggle_minimap",
-            "Toggle Minimap",
-            true,
-            None,
-        ));
-        let _ = tray_menu.append(&MenuItem::with_id("exit", "Exit", true, None));
-        TrayIconBuilder::new()
-            .with_menu(Box::new(tray_menu))
-            .with_tooltip("Oxide+")
-            .with_icon(LaunchConfig::tray_icon(ICON))
-            .build()
-            .unwrap()
+    // Filled in once the tray exists, so saved presets can rebuild its entries
+    let presets_menu: Rc<RefCell<Option<Submenu>>> = Rc::new(RefCell::new(None));
+
+    let tray_icon = {
+        let presets_menu = presets_menu.clone();
+        move || {
+            let tray_menu = Menu::new();
+            let _ = tray_menu.append(&MenuItem::with_id("show", "Show", true, None));
+            let _ = tray_menu.append(&MenuItem::with_id(
+                "toggle_minimap",
+                "Toggle Minimap",
+                true,
+                None,
+            ));
+            let submenu = Submenu::new("Minimap Preset", true);
+            let minimap_presets = load_minimap_presets().ok().flatten().unwrap_or_default();
+            rebuild_presets_menu(&submenu, &minimap_presets);
+            let _ = tray_menu.append(&submenu);
+            *presets_menu.borrow_mut() = Some(submenu);
+            let _ = tray_menu.append(&MenuItem::with_id("exit", "Exit", true, None));
+            TrayIconBuilder::new()
+                .with_menu(Box::new(tray_menu))
+                .with_tooltip("Oxide+")
+                .with_icon(LaunchConfig::tray_icon(ICON))
+                .build()
+                .unwrap()
+        }
     };
     let tray_handler = move |ev, mut ctx: RendererContext| match ev {
         TrayEvent::Menu(MenuEvent { id }) if id == "show" => {
@@ -114,6 +133,16 @@ fn main() {
                 .unbounded_send(ChannelSend::ToggleMinimap(!minimap_state))
                 .unwrap();
         }
+        TrayEvent::Menu(MenuEvent { id }) if id.0.starts_with(MINIMAP_PRESET_MENU_PREFIX) => {
+            let name = id.0[MINIMAP_PRESET_MENU_PREFIX.len()..].to_string();
+            radio_station
+                .read()
+                .state_tx
+                .clone()
+                .unwrap()
+                .unbounded_send(ChannelSend::ApplyMinimapPreset(name))
+                .unwrap();
+        }
         TrayEvent::Menu(MenuEvent { id }) if id == "exit" => {
             ctx.exit();
         }
@@ -139,6 +168,11 @@ fn main() {
                 let mut poller = Poller::new(None, state_tx.clone());
 
                 let mut window_id: Option<WindowId> = None;
+                let mut geofence_tracker = GeofenceTracker::default();
+                // Server and wipe the wipe-scoped data was last loaded for
+                let mut wipe_scope: Option<(String, u32)> = None;
+                // Whether markers arrived since the poller (re)started, spawns before that were missed
+                let mut markers_connected = false;
 
                 radio_station.write_channel(DataChannel::MinimapSettingsUpdate).settings.minimap_settings = match load_minimap_settings() {
                     Ok(minimap_settings) => {
@@ -165,6 +199,22 @@ fn main() {
                     }
                 };
 
+                radio_station.write_channel(DataChannel::NotificationSettingsUpdate).settings.notification_settings = match load_notification_settings() {
+                    Ok(notification_settings) => notification_settings.unwrap_or_default(),
+                    Err(err) => {
+                        println!("Error loading notification settings: {:?}", err);
+                        NotificationSettings::default()
+                    }
+                };
+
+                radio_station.write_channel(DataChannel::MinimapPresetsUpdate).settings.minimap_presets = match load_minimap_presets() {
+                    Ok(minimap_presets) => minimap_presets.unwrap_or_default(),
+                    Err(err) => {
+                        println!("Error loading minimap presets: {:?}", err);
+                        MinimapPresets::default()
+                    }
+                };
+
                 while let Some(channel_data) = state_rx.next().await {
                     match channel_data {
                         ChannelSend::UserDataUpdate(user_data) => {
@@ -197,9 +247,15 @@ fn main() {
                                     poller.update_details(None);
                                 }
                             }
+                            let server_preset = selected_server.as_ref().and_then(|server| {
+                                radio_station.read().settings.minimap_presets.server_defaults.get(&server.id).cloned()
+                            });
                             radio_station
                                 .write_channel(DataChannel::SelectedServerUpdate)
                                 .selected_server = selected_server;
+                            if let Some(name) = server_preset {
+                                apply_minimap_preset(radio_station, &name);
+                            }
                         }
                         ChannelSend::InfoStateUpdate(info_state) => {
                             radio_station
@@ -226,22 +282,144 @@ fn main() {
                                 } else {
                                     InfoState::default()
                                 };
+
+                            let server_id = radio_station.read().selected_server.as_ref().map(|s| s.id.clone());
+                            let wipe_time = radio_station.read().info_state.wipe_time;
+
+                            let info_state = radio_station.read().info_state.clone();
+                            if let Some(server_id) = &server_id
+                                && let Some(players) = info_state.players
+                            {
+                                let sample = PopulationSample {
+                                    timestamp: chrono::Utc::now().timestamp(),
+                                    players,
+                                    max_players: info_state.max_players.unwrap_or(0),
+                                    queued_players: info_state.queued_players.unwrap_or(0),
+                                };
+                                if let Err(err) = append_population(server_id, &sample) {
+                                    println!("Error saving population: {:?}", err);
+                                }
+                                let cutoff = sample.timestamp - POPULATION_RETENTION_SECS;
+                                let mut data = radio_station.write_channel(DataChannel::PopulationUpdate);
+                                data.population.retain(|s| s.timestamp >= cutoff);
+                                data.population.push(sample);
+                            }
+
+                            // Info is polled periodically, so only a new server or wipe changes the stored scope
+                            let scope = server_id.clone().zip(wipe_time);
+                            if scope != wipe_scope {
+                                wipe_scope = scope;
+                                if let (Some(server_id), Some(wipe_time)) = (&server_id, wipe_time) {
+                                    match check_wipe(server_id, wipe_time) {
+                                        Ok(Some(previous_wipe_time)) => {
+                                            if let Err(err) = archive_wipe(server_id, previous_wipe_time) {
+                                                println!("Error archiving wipe: {:?}", err);
+                                            }
+                                            radio_station
+                                                .write_channel(DataChannel::TrailsUpdate)
+                                                .trails = HashMap::new();
+                                            push_notification(
+                                                radio_station,
+                                                NotificationKind::Wipe,
+                                                None,
+                                                ToastData {
+                                                    title: "Server wiped".to_string(),
+                                                    message: radio_station.read().info_state.name.clone().unwrap_or_default(),
+                                                    timeout: Timeout::Default,
+                                                    on_press: None,
+                                                },
+                                            );
+                                        }
+                                        Ok(None) => {}
+                                        Err(err) => {
+                                            println!("Error checking wipe: {:?}", err);
+                                        }
+                                    }
+                                }
+
+                                radio_station
+                                    .write_channel(DataChannel::TimelineUpdate)
+                                    .timeline = match (&server_id, wipe_time) {
+                                        (Some(server_id), Some(wipe_time)) => match load_timeline(server_id, wipe_time) {
+                                            Ok(timeline) => timeline,
+                                            Err(err) => {
+                                                println!("Error loading timeline: {:?}", err);
+                                                Vec::new()
+                                            }
+                                        },
+                                        _ => Vec::new(),
+                                    };
+                                radio_station
+                                    .write_channel(DataChannel::HistoryUpdate)
+                                    .history = match (&server_id, wipe_time) {
+                                        (Some(server_id), Some(wipe_time)) => match load_history(server_id, wipe_time) {
+                                            Ok(history) => history,
+                                            Err(err) => {
+                                                println!("Error loading history: {:?}", err);
+                                                Vec::new()
+                                            }
+                                        },
+                                        _ => Vec::new(),
+                                    };
+                                radio_station
+                                    .write_channel(DataChannel::PlaybackUpdate)
+                                    .playback
+                                    .time = None;
+                                radio_station
+                                    .write_channel(DataChannel::PopulationUpdate)
+                                    .population = match &server_id {
+                                        Some(server_id) if wipe_time.is_some() => match load_population(server_id) {
+                                            Ok(population) => population,
+                                            Err(err) => {
+                                                println!("Error loading population: {:?}", err);
+                                                Vec::new()
+                                            }
+                                        },
+                                        _ => Vec::new(),
+                                    };
+                                radio_station
+                                    .write_channel(DataChannel::SpawnLogUpdate)
+                                    .spawn_log = match &server_id {
+                                        Some(server_id) if wipe_time.is_some() => match load_spawn_log(server_id) {
+                                            Ok(spawn_log) => spawn_log,
+                                            Err(err) => {
+                                                println!("Error loading spawn log: {:?}", err);
+                                                SpawnLog::default()
+                                            }
+                                        },
+                                        _ => SpawnLog::default(),
+                                    };
+                                radio_station
+                                    .write_channel(DataChannel::GeofencesUpdate)
+                                    .geofences = match (&server_id, wipe_time) {
+                                        (Some(server_id), Some(wipe_time)) => match load_geofences(server_id, wipe_time) {
+                                            Ok(geofences) => geofences,
+                                            Err(err) => {
+                                                println!("Error loading geofences: {:?}", err);
+                                                Vec::new()
+                                            }
+                                        },
+                                        _ => Vec::new(),
+                                    };
+                                geofence_tracker.clear();
+                                radio_station
+                                    .write_channel(DataChannel::AnnotationsUpdate)
+                                    .annotations = match (&server_id, wipe_time) {
+                                        (Some(server_id), Some(wipe_time)) => match load_annotations(server_id, wipe_time) {
+                                            Ok(annotations) => annotations,
+                                            Err(err) => {
+                                                println!("Error loading annotations: {:?}", err);
+                                                Vec::new()
+                                            }
+                                        },
+                                        _ => Vec::new(),
+                                    };
+                            }
                         }
                         ChannelSend::MapStateUpdate(map_state) => {
                             radio_station
                                 .write_channel(DataChannel::MapStateUpdate)
-                                .map_state = if let Some(map_state) = map_state {
-                                    MapState {
-                                        width: map_state.width,
-                                        height: map_state.height,
-                                        jpg_image: map_state.jpg_image,
-                                        ocean_margin: map_state.ocean_margin,
-                                        monuments: map_state.monuments,
-                                        background: Some(map_state.background),
-                                    }
-                                } else {
-                                    MapState::default()
-                                };
+                                .map_state = map_state.unwrap_or_default();
                         }
                         ChannelSend::MapMarkersUpdate(map_markers) => {
                             let old_map_markers = radio_station
@@ -250,17 +428,110 @@ fn main() {
                                 .clone();
 
                             if let Some(map_markers) = &map_markers {
-                                if old_map_markers.markers != map_markers.markers {
-                                    radio_station
-                                        .write_channel(DataChannel::MapMarkersUpdate)
-                                        .map_markers = MapMarkers {
-                                            markers: map_markers.markers.clone(),
+                                if !markers_connected {
+                                    markers_connected = true;
+                                    mark_spawns_connected(radio_station);
+                                }
+
+                                // An empty old list means we just connected, so everything would look new
+                                if !old_map_markers.markers.is_empty() {
+                                    let map_size = radio_station.read().info_state.map_size;
+                                    let monuments = radio_station.read().map_state.monuments.clone();
+                                    for marker in &map_markers.markers {
+                                        if old_map_markers.markers.contains_key(&marker.id) {
+                                            continue;
                                         }
+                                        if let Some(spawn_event) = SpawnEvent::from_marker_type(marker.r#type()) {
+                                            record_spawn(radio_station, spawn_event);
+                                        }
+                                        let kind = match marker.r#type() {
+                                            AppMarkerType::CargoShip => TimelineEventKind::CargoShip { marker_id: marker.id },
+                                            AppMarkerType::PatrolHelicopter => TimelineEventKind::PatrolHelicopter { marker_id: marker.id },
+                                            AppMarkerType::Ch47 => TimelineEventKind::Chinook { marker_id: marker.id },
+                                            AppMarkerType::Explosion => TimelineEventKind::Explosion { marker_id: marker.id },
+                                            _ => continue,
+                                        };
+                                        push_timeline_event(
+                                            radio_station,
+                                            TimelineEvent::new(kind).at(marker.x, marker.y, map_size).near(&monuments),
+                                        );
+                                    }
+                                }
+
+                                let geofences = radio_station.read().geofences.clone();
+                                for old_marker in old_map_markers.markers.values() {
+                                    if !map_markers.markers.iter().any(|marker| marker.id == old_marker.id) {
+                                        geofence_tracker.forget(GeofenceSubject::Marker(old_marker.id));
+
+                                        // Despawned markers can't be followed anymore, so go back to following me
+                                        let despawned = MapTarget::Marker(old_marker.id);
+                                        if radio_station.read().follow_target.as_ref() == Some(&despawned) {
+                                            radio_station
+                                                .write_channel(DataChannel::FollowUpdate)
+                                                .follow_target = None;
+                                        }
+                                    }
+                                }
+                                for marker in &map_markers.markers {
+                                    let Some(spawn_event) = SpawnEvent::from_marker_type(marker.r#type()) else {
+                                        continue;
+                                    };
+                                    for (geofence, entered) in geofence_tracker.update(
+                                        &geofences,
+                                        GeofenceSubject::Marker(marker.id),
+                                        Some(spawn_event),
+                                        marker.x,
+                                        marker.y,
+                                    ) {
+                                        geofence_alert(radio_station, &geofence, spawn_event.name().to_string(), None, entered, marker.x, marker.y);
+                                    }
+                                }
+
+                                let removed: Vec<u32> = old_map_markers
+                                    .markers
+                                    .keys()
+                                    .filter(|id| !map_markers.markers.iter().any(|marker| marker.id == **id))
+                                    .copied()
+                                    .collect();
+                                if !removed.is_empty() {
+                                    let mut data = radio_station.write_channel(DataChannel::MarkersRemoved);
+                                    for id in removed {
+                                        data.map_markers.markers.remove(&id);
+                                    }
+                                }
+
+                                let added: Vec<&AppMarker> = map_markers
+                                    .markers
+                                    .iter()
+                                    .filter(|marker| !old_map_markers.markers.contains_key(&marker.id))
+                                    .collect();
+                                if !added.is_empty() {
+                                    let mut data = radio_station.write_channel(DataChannel::MarkersAdded);
+                                    for marker in added {
+                                        data.map_markers.markers.insert(marker.id, marker.clone());
+                                    }
+                                }
+
+                                // Only markers that actually changed are written, so a moving cargo ship
+                                // doesn't rerender every vending machine
+                                for marker in &map_markers.markers {
+                                    let Some(old_marker) = old_map_markers.markers.get(&marker.id) else {
+                                        continue;
+                                    };
+                                    if old_marker == marker {
+                                        continue;
+                                    }
+                                    radio_station
+                                        .write_channel(DataChannel::MarkerUpdate(marker.id))
+                                        .map_markers
+                                        .markers
+                                        .insert(marker.id, marker.clone());
                                 }
                             }
                             else {
+                                markers_connected = false;
                                 radio_station
-                                    .write_channel(DataChannel::MapMarkersUpdate)
+                                    .write_channel(DataChannel::MarkersRemoved)
                                     .map_markers = MapMarkers::default();
                             }
                         }
@@ -268,6 +539,46 @@ fn main() {
                             let old_team_info = radio_station.read().team_info.clone();
 
                             if let Some(team_info) = &team_info {
+                                let timestamp = chrono::Utc::now().timestamp();
+                                let trail_steps = {
+                                    let data = radio_station.read();
+                                    team_info.members.iter().filter_map(|member| {
+                                        trail_step(data.trails.get(&member.steam_id), member.x, member.y, member.is_alive && member.is_online)
+                                            .map(|step| (member.steam_id, step, TrailPoint::new(timestamp, member.x, member.y)))
+                                    }).collect::<Vec<_>>()
+                                };
+                                if !trail_steps.is_empty() {
+                                    let mut data = radio_station.write_channel(DataChannel::TrailsUpdate);
+                                    for (steam_id, step, point) in trail_steps {
+                                        apply_trail_step(data.trails.entry(steam_id).or_default(), step, point);
+                                    }
+                                }
+
+                                let geofences = radio_station.read().geofences.clone();
+                                for member in &team_info.members {
+                                    // Dead or offline members start over, so the respawn or login position
+                                    // doesn't count as leaving the zone they died or logged off in
+                                    if !member.is_online || !member.is_alive {
+                                        geofence_tracker.forget(GeofenceSubject::Member(member.steam_id));
+                                        continue;
+                                    }
+                                    for (geofence, entered) in geofence_tracker.update(
+                                        &geofences,
+                                        GeofenceSubject::Member(member.steam_id),
+                                        None,
+                                        member.x,
+                                        member.y,
+                                    ) {
+                                        geofence_alert(radio_station, &geofence, member.name.clone(), Some(member.steam_id), entered, member.x, member.y);
+                                    }
+                                }
+
+                                let last_snapshot = radio_station.read().history.last().map(|snapshot| snapshot.timestamp);
+                                if last_snapshot.is_none_or(|last| timestamp - last >= HISTORY_INTERVAL_SECS) {
+                                    let snapshot = HistorySnapshot::new(timestamp, team_info, &radio_station.read().map_markers.markers);
+                                    record_history(radio_station, snapshot);
+                                }
+
                                 if old_team_info.leader_steam_id != Some(team_info.leader_steam_id) {
                                     radio_station
                                         .write_channel(DataChannel::TeamLeaderUpdate)
@@ -309,6 +620,62 @@ fn main() {
                                         continue;
                                     }
                                     let old_member = old_member.unwrap();
+
+                                    let map_size = radio_station.read().info_state.map_size;
+                                    let monuments = radio_station.read().map_state.monuments.clone();
+                                    let mut presence_events = Vec::new();
+                                    if member.is_online != old_member.is_online {
+                                        presence_events.push(if member.is_online {
+                                            (
+                                                NotificationKind::TeammateOnline,
+                                                TimelineEvent::new(TimelineEventKind::TeammateOnline { steam_id: member.steam_id, name: member.name.clone() })
+                                                    .at(member.x, member.y, map_size)
+                                                    .near(&monuments),
+                                            )
+                                        } else {
+                                            (
+                                                NotificationKind::TeammateOffline,
+                                                TimelineEvent::new(TimelineEventKind::TeammateOffline { steam_id: member.steam_id, name: member.name.clone() })
+                                                    .at(member.x, member.y, map_size)
+                                                    .near(&monuments),
+                                            )
+                                        });
+                                    }
+                                    if old_member.is_alive && !member.is_alive {
+                                        presence_events.push((
+                                            NotificationKind::TeammateDied,
+                                            TimelineEvent::new(TimelineEventKind::TeammateDied { steam_id: member.steam_id, name: member.name.clone() })
+                                                .at(old_member.x, old_member.y, map_size)
+                                                .near(&monuments),
+                                        ));
+                                    }
+                                    if member.spawn_time != old_member.spawn_time && member.is_alive {
+                                        presence_events.push((
+                                            NotificationKind::TeammateSpawned,
+                                            TimelineEvent::new(TimelineEventKind::TeammateSpawned { steam_id: member.steam_id, name: member.name.clone() })
+                                                .at(member.x, member.y, map_size)
+                                                .near(&monuments),
+                                        ));
+                                    }
+                                    for (notification_kind, event) in presence_events {
+                                        let title = match (notification_kind, event.place()) {
+                                            (NotificationKind::TeammateDied, Some(place)) => format!("{} at {}", event.kind.title(), place),
+                                            _ => event.kind.title(),
+                                        };
+                                        push_notification(
+                                            radio_station,
+                                            notification_kind,
+                                            Some(member.steam_id),
+                                            ToastData {
+                                                title,
+                                                message: radio_station.read().info_state.name.clone().unwrap_or_default(),
+                                                timeout: Timeout::Default,
+                                                on_press: None,
+                                            },
+                                        );
+                                        push_timeline_event(radio_station, event);
+                                    }
+
                                     if member.steam_id == old_member.steam_id
                                     && member.name == old_member.name
                                     && member.x == old_member.x
@@ -354,6 +721,9 @@ fn main() {
                                     .write_channel(DataChannel::TeamLeaderUpdate)
                                     .team_info
                                     .leader_steam_id = None;
+                                radio_station
+                                    .write_channel(DataChannel::TrailsUpdate)
+                                    .trails = HashMap::new();
                             }
                         }
                         ChannelSend::ToggleMinimap(toggle) => {
@@ -465,24 +835,80 @@ fn main() {
                             }
                         }
                         ChannelSend::AddToast(toast_data) => {
-                            let mut rng = rand::rng();
-                            let toast_id: u64 = rng.next_u64();
-                            radio_station.write_channel(DataChannel::ToastsUpdate).toasts.insert(
-                                toast_id,
-                                Toast {
-                                    id: toast_id,
-                                    title: toast_data.title,
-                                    message: toast_data.message,
-                                    timeout: toast_data.timeout,
-                                    on_press: toast_data.on_press.map(EventHandler::new),
-                                },
-                            );
+                            push_toast(radio_station, toast_data);
+                        },
+                        ChannelSend::Notification(notification_kind, toast_data) => {
+                            push_notification(radio_station, notification_kind, None, toast_data);
                         },
                         ChannelSend::ModalUpdate(overlay) => {
                             radio_station
                                 .write_channel(DataChannel::ModalUpdate)
                                 .modal = overlay;
                         },
+                        ChannelSend::GeofencesUpdate(geofences) => {
+                            let server_id = radio_station.read().selected_server.as_ref().map(|s| s.id.clone());
+                            let wipe_time = radio_station.read().info_state.wipe_time;
+                            if let (Some(server_id), Some(wipe_time)) = (server_id, wipe_time) {
+                                if let Err(err) = save_geofences(&server_id, wipe_time, &geofences) {
+                                    println!("Error saving geofences: {:?}", err);
+                                }
+                            }
+                            radio_station
+                                .write_channel(DataChannel::GeofencesUpdate)
+                                .geofences = geofences;
+                        },
+                        ChannelSend::AnnotationsUpdate(annotations) => {
+                            let server_id = radio_station.read().selected_server.as_ref().map(|s| s.id.clone());
+                            let wipe_time = radio_station.read().info_state.wipe_time;
+                            if let (Some(server_id), Some(wipe_time)) = (server_id, wipe_time) {
+                                if let Err(err) = save_annotations(&server_id, wipe_time, &annotations) {
+                                    println!("Error saving annotations: {:?}", err);
+                                }
+                            }
+                            radio_station
+                                .write_channel(DataChannel::AnnotationsUpdate)
+                                .annotations = annotations;
+                        },
+                        ChannelSend::MinimapPresetsUpdate(minimap_presets) => {
+                            if let Err(err) = save_minimap_presets(&minimap_presets) {
+                                println!("Error saving minimap presets: {:?}", err);
+                            }
+                            if let Some(submenu) = presets_menu.borrow().as_ref() {
+                                rebuild_presets_menu(submenu, &minimap_presets);
+                            }
+                            radio_station
+                                .write_channel(DataChannel::MinimapPresetsUpdate)
+                                .settings
+                                .minimap_presets = minimap_presets;
+                        },
+                        ChannelSend::ApplyMinimapPreset(name) => {
+                            apply_minimap_preset(radio_station, &name);
+                        },
+                        ChannelSend::AddTimelineEvent(server_id, event) => {
+                            let selected_server_id = radio_station.read().selected_server.as_ref().map(|s| s.id.clone());
+                            if selected_server_id == Some(server_id) {
+                                push_timeline_event(radio_station, event);
+                            }
+                        },
+                        ChannelSend::TeamMessage { steam_id, name, message } => {
+                            let me_steam_id = radio_station.read().user_data.steam_id.as_ref().and_then(|id| id.parse::<u64>().ok());
+                            let me_name = me_steam_id.and_then(|id| radio_station.read().team_info.members.get(&id).map(|member| member.name.clone()));
+                            let Some(me_name) = me_name else {
+                                continue;
+                            };
+                            if Some(steam_id) == me_steam_id || !message.to_lowercase().contains(&me_name.to_lowercase()) {
+                                continue;
+                            }
+
+                            let map_size = radio_station.read().info_state.map_size;
+                            let monuments = radio_station.read().map_state.monuments.clone();
+                            let sender = radio_station.read().team_info.members.get(&steam_id).map(|member| (member.x, member.y));
+                            let mut event = TimelineEvent::new(TimelineEventKind::ChatMention { steam_id, name, message });
+                            if let Some((x, y)) = sender {
+                                event = event.at(x, y, map_size).near(&monuments);
+                            }
+                            push_timeline_event(radio_station, event);
+                        },
                     }
                 }
             })
@@ -518,10 +944,168 @@ fn main() {
     );
 }
 
+/// Replaces the tray's preset entries with the saved presets.
+fn rebuild_presets_menu(submenu: &Submenu, minimap_presets: &MinimapPresets) {
+    while submenu.remove_at(0).is_some() {}
+    for preset in &minimap_presets.presets {
+        let _ = submenu.append(&MenuItem::with_id(
+            format!("{MINIMAP_PRESET_MENU_PREFIX}{}", preset.name),
+            &preset.name,
+            true,
+            None,
+        ));
+    }
+}
+
+/// Switches the minimap to a saved preset, keeping it shown or hidden as it is, and
+/// remembers it as the active one.
+fn apply_minimap_preset(mut radio_station: RadioStation<Data, DataChannel>, name: &str) {
+    let Some(preset) = radio_station.read().settings.minimap_presets.get(name).cloned() else {
+        println!("[apply_minimap_preset] No preset named {name}");
+        return;
+    };
+
+    {
+        let mut data = radio_station.write_channel(DataChannel::MinimapSettingsUpdate);
+        data.settings.minimap_settings = preset.apply(&data.settings.minimap_settings);
+    }
+
+    let mut data = radio_station.write_channel(DataChannel::MinimapPresetsUpdate);
+    data.settings.minimap_presets.active = Some(preset.name);
+    if let Err(err) = save_minimap_presets(&data.settings.minimap_presets) {
+        println!("Error saving minimap presets: {:?}", err);
+    }
+}
+
+fn push_toast(mut radio_station: RadioStation<Data, DataChannel>, toast_data: ToastData) {
+    let mut rng = rand::rng();
+    let toast_id: u64 = rng.next_u64();
+    radio_station.write_channel(DataChannel::ToastsUpdate).toasts.insert(
+        toast_id,
+        Toast {
+            id: toast_id,
+            title: toast_data.title,
+            message: toast_data.message,
+            timeout: toast_data.timeout,
+            on_press: toast_data.on_press.map(EventHandler::new),
+        },
+    );
+}
+
+/// Shows the toast only if the user's notification settings allow it.
+fn push_notification(
+    radio_station: RadioStation<Data, DataChannel>,
+    notification_kind: NotificationKind,
+    steam_id: Option<u64>,
+    toast_data: ToastData,
+) {
+    if radio_station
+        .read()
+        .settings
+        .notification_settings
+        .allows(notification_kind, steam_id)
+    {
+        push_toast(radio_station, toast_data);
+    }
+}
+
+fn push_timeline_event(mut radio_station: RadioStation<Data, DataChannel>, event: TimelineEvent) {
+    let server_id = radio_station.read().selected_server.as_ref().map(|s| s.id.clone());
+    let wipe_time = radio_station.read().info_state.wipe_time;
+
+    if let (Some(server_id), Some(wipe_time)) = (server_id, wipe_time) {
+        if let Err(err) = append_timeline(&server_id, wipe_time, &event) {
+            println!("Error saving timeline: {:?}", err);
+        }
+    }
+
+    radio_station
+        .write_channel(DataChannel::TimelineUpdate)
+        .timeline
+        .push(event);
+}
+
+fn geofence_alert(
+    radio_station: RadioStation<Data, DataChannel>,
+    geofence: &Geofence,
+    subject: String,
+    steam_id: Option<u64>,
+    entered: bool,
+    x: f32,
+    y: f32,
+) {
+    let map_size = radio_station.read().info_state.map_size;
+    let monuments = radio_station.read().map_state.monuments.clone();
+    let event = TimelineEvent::new(TimelineEventKind::Geofence {
+        zone: geofence.name.clone(),
+        subject,
+        entered,
+    })
+    .at(x, y, map_size)
+    .near(&monuments);
+
+    push_notification(
+        radio_station,
+        NotificationKind::Geofence,
+        steam_id,
+        ToastData {
+            title: event.kind.title(),
+            message: event.place().unwrap_or_default(),
+            timeout: Timeout::Default,
+            on_press: None,
+        },
+    );
+    push_timeline_event(radio_station, event);
+}
+
+fn record_spawn(mut radio_station: RadioStation<Data, DataChannel>, spawn_event: SpawnEvent) {
+    let server_id = radio_station.read().selected_server.as_ref().map(|s| s.id.clone());
+
+    let mut data = radio_station.write_channel(DataChannel::SpawnLogUpdate);
+    data.spawn_log.record(spawn_event, chrono::Utc::now().timestamp());
+
+    if let Some(server_id) = server_id {
+        if let Err(err) = save_spawn_log(&server_id, &data.spawn_log) {
+            println!("Error saving spawn log: {:?}", err);
+        }
+    }
+}
+
+fn mark_spawns_connected(mut radio_station: RadioStation<Data, DataChannel>) {
+    let server_id = radio_station.read().selected_server.as_ref().map(|s| s.id.clone());
+
+    let mut data = radio_station.write_channel(DataChannel::SpawnLogUpdate);
+    data.spawn_log.connected(chrono::Utc::now().timestamp());
+
+    if let Some(server_id) = server_id {
+        if let Err(err) = save_spawn_log(&server_id, &data.spawn_log) {
+            println!("Error saving spawn log: {:?}", err);
+        }
+    }
+}
+
+fn record_history(mut radio_station: RadioStation<Data, DataChannel>, snapshot: HistorySnapshot) {
+    let server_id = radio_station.read().selected_server.as_ref().map(|s| s.id.clone());
+    let wipe_time = radio_station.read().info_state.wipe_time;
+
+    if let (Some(server_id), Some(wipe_time)) = (server_id, wipe_time) {
+        if let Err(err) = append_history(&server_id, wipe_time, &snapshot) {
+            println!("Error saving history: {:?}", err);
+        }
+    }
+
+    radio_station
+        .write_channel(DataChannel::HistoryUpdate)
+        .history
+        .push(snapshot);
+}
+
 #[derive(Default, Clone)]
 pub struct Settings {
     pub map_settings: MapSettings,
     pub minimap_settings: MinimapSettings,
+    pub minimap_presets: MinimapPresets,
+    pub notification_settings: NotificationSettings,
 }
 
 #[derive(Default, Clone)]
@@ -544,7 +1128,7 @@ pub struct InfoState {
     pub cameras_enabled: Option<bool>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct TeamMember {
     pub steam_id: u64,
     pub name: String,
@@ -569,7 +1153,8 @@ pub struct TeamInfo {
 pub struct MapState {
     pub width: u32,
     pub height: u32,
-    pub jpg_image: Vec<u8>,
+    /// Decoded once by the poller, `None` until the map arrives.
+    pub image: Option<MapImage>,
     pub ocean_margin: i32,
     pub monuments: Vec<Monument>,
     pub background: Option<String>,
@@ -577,7 +1162,7 @@ pub struct MapState {
 
 #[derive(Default, Clone, Debug)]
 pub struct MapMarkers {
-    pub markers: Vec<AppMarker>,
+    pub markers: HashMap<u32, AppMarker>,
 }
 
 #[derive(Default, Clone)]
@@ -592,6 +1177,25 @@ pub struct Data {
     pub map_state: MapState,
     pub map_markers: MapMarkers,
     pub team_info: TeamInfo,
+    pub timeline: Vec<TimelineEvent>,
+    pub trails: HashMap<u64, VecDeque<TrailPoint>>,
+    pub map_focus: Option<Point2D<f32, ()>>,
+    pub history: Vec<HistorySnapshot>,
+    pub population: Vec<PopulationSample>,
+    pub spawn_log: SpawnLog,
+    pub geofences: Vec<Geofence>,
+    pub geofence_draft: Option<GeofenceDraft>,
+    pub annotations: Vec<Annotation>,
+    pub annotation_draft: Option<AnnotationDraft>,
+    /// Points of the route being measured, `None` while the measuring tool is off.
+    pub measure_route: Option<Vec<(f32, f32)>>,
+    pub hovered_target: Option<MapTarget>,
+    pub pinned_targets: Vec<MapTarget>,
+    /// What centred maps follow, `None` follows the local player.
+    pub follow_target: Option<MapTarget>,
+    /// Monument whose detail panel is open on the map.
+    pub selected_monument: Option<Monument>,
+    pub playback: Playback,
 
     pub state_tx: Option<futures_channel::mpsc::UnboundedSender<ChannelSend>>,
     pub minimap_window_id: Option<WindowId>,
@@ -612,20 +1216,51 @@ pub enum DataChannel {
     SelectedServerUpdate,
     InfoStateUpdate,
     MapStateUpdate,
+    /// Notified along with `MarkersAdded` and `MarkersRemoved`, never for moving markers.
     MapMarkersUpdate,
+    MarkersAdded,
+    MarkersRemoved,
+    MarkerUpdate(u32),
     TeamLeaderUpdate,
     MapNotesUpdate,
     TeamMembersUpdate,
     TeamMemberUpdate(u64),
+    TimelineUpdate,
+    TrailsUpdate,
+    MapFocusUpdate,
+    HistoryUpdate,
+    PlaybackUpdate,
+    PopulationUpdate,
+    SpawnLogUpdate,
+    GeofencesUpdate,
+    GeofenceDraftUpdate,
+    AnnotationsUpdate,
+    AnnotationDraftUpdate,
+    MeasureUpdate,
+    TargetsUpdate,
+    FollowUpdate,
+    MonumentSelectUpdate,
     SettingsUpdate,
     MapSettingsUpdate,
     MinimapSettingsUpdate,
+    MinimapPresetsUpdate,
+    NotificationSettingsUpdate,
     MonitorSizeUpdate,
     ToastsUpdate,
     ModalUpdate,
 }
 
-impl RadioChannel<Data> for DataChannel {}
+impl RadioChannel<Data> for DataChannel {
+    fn derive_channel(self, _radio: &Data) -> Vec<Self> {
+        match self {
+            // Whole-list readers only need to know when markers come and go
+            DataChannel::MarkersAdded | DataChannel::MarkersRemoved => {
+                vec![self, DataChannel::MapMarkersUpdate]
+            }
+            _ => vec![self],
+        }
+    }
+}
 
 pub struct ToastData {
     pub title: String,
@@ -641,10 +1276,17 @@ pub enum ChannelSend {
     AddServer(ServerData),
     SelectedServerUpdate(Option<ServerData>),
     InfoStateUpdate(Option<AppInfo>),
-    MapStateUpdate(Option<AppMap>),
+    MapStateUpdate(Option<MapState>),
     MapMarkersUpdate(Option<AppMapMarkers>),
     TeamInfoUpdate(Option<AppTeamInfo>),
     ToggleMinimap(bool),
     AddToast(ToastData),
+    Notification(NotificationKind, ToastData),
     ModalUpdate(Option<Modal>),
+    AddTimelineEvent(String, TimelineEvent),
+    TeamMessage { steam_id: u64, name: String, message: String },
+    GeofencesUpdate(Vec<Geofence>),
+    AnnotationsUpdate(Vec<Annotation>),
+    MinimapPresetsUpdate(MinimapPresets),
+    ApplyMinimapPreset(String),
 }
@@ -0,0 +1,448 @@
+use euclid::Point2D;
+use freya::{
+    prelude::*,
+    radio::{Radio, use_radio},
+};
+use freya_router::prelude::RouterContext;
+
+use crate::{
+    ChannelSend, Data, DataChannel,
+    app::Route,
+    colors,
+    components::{Button, Timeout},
+    utils::{
+        ANNOTATION_COLORS, ANNOTATION_ICONS, Annotation, AnnotationDraft, AnnotationExport,
+        AnnotationTool, create_toast, export_annotations, grid_reference, import_annotations,
+        index_to_color, index_to_icon, merge_annotations,
+    },
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum ImportMode {
+    Merge,
+    Replace,
+}
+
+#[derive(PartialEq)]
+pub struct AnnotationsPage {}
+
+impl Component for AnnotationsPage {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::AnnotationsUpdate);
+        let annotations = radio.slice_current(|s| &s.annotations);
+        let map_size = radio.slice(DataChannel::InfoStateUpdate, |s| &s.info_state.map_size);
+
+        let mut start_drawing = move |tool: AnnotationTool| {
+            radio
+                .write_channel(DataChannel::GeofenceDraftUpdate)
+                .geofence_draft = None;
+            radio
+                .write_channel(DataChannel::AnnotationDraftUpdate)
+                .annotation_draft = Some(AnnotationDraft::new(tool));
+            radio
+                .write_channel(DataChannel::MapSettingsUpdate)
+                .settings
+                .map_settings
+                .annotations = true;
+            RouterContext::get().replace(Route::Map);
+        };
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::Fill)
+            .background(Color::from_hex(colors::BACKGROUND).unwrap())
+            .corner_radius(8.0)
+            .child(
+                ScrollView::new()
+                    .width(Size::Fill)
+                    .height(Size::Fill)
+                    .child(
+                        rect()
+                            .padding(8.0)
+                            .spacing(4.0)
+                            .child(
+                                rect()
+                                    .width(Size::Fill)
+                                    .height(Size::px(48.0))
+                                    .padding(8.0)
+                                    .spacing(8.0)
+                                    .direction(Direction::Horizontal)
+                                    .cross_align(Alignment::Center)
+                                    .content(Content::Flex)
+                                    .children([
+                                        svg(freya_icons::lucide::map_pin())
+                                            .height(Size::Fill)
+                                            .color(Color::from_hex(colors::SELECT).unwrap())
+                                            .into(),
+                                        label()
+                                            .width(Size::flex(1.0))
+                                            .font_size(24.0)
+                                            .font_weight(FontWeight::BOLD)
+                                            .color(Color::from_hex(colors::TEXT).unwrap())
+                                            .text("ANNOTATIONS")
+                                            .into(),
+                                    ])
+                                    .children(
+                                        [
+                                            (AnnotationTool::Pin, freya_icons::lucide::map_pin()),
+                                            (AnnotationTool::Line, freya_icons::lucide::spline()),
+                                            (
+                                                AnnotationTool::Polygon,
+                                                freya_icons::lucide::pentagon(),
+                                            ),
+                                        ]
+                                        .into_iter()
+                                        .map(|(tool, icon)| {
+                                            Button::new()
+                                                .height(Size::Fill)
+                                                .padding((4.0, 8.0))
+                                                .corner_radius(4.0)
+                                                .icon(icon)
+                                                .text(format!("NEW {}", tool.title()))
+                                                .on_press(move |_| start_drawing(tool))
+                                                .into()
+                                        })
+                                        .collect::<Vec<Element>>(),
+                                    )
+                                    .children([
+                                        Button::new()
+                                            .height(Size::Fill)
+                                            .padding((4.0, 8.0))
+                                            .corner_radius(4.0)
+                                            .icon(freya_icons::lucide::download())
+                                            .text("IMPORT")
+                                            .on_press(move |_| import(radio, ImportMode::Merge))
+                                            .into(),
+                                        Button::new()
+                                            .height(Size::Fill)
+                                            .padding((4.0, 8.0))
+                                            .corner_radius(4.0)
+                                            .icon(freya_icons::lucide::replace())
+                                            .text("REPLACE")
+                                            .on_press(move |_| import(radio, ImportMode::Replace))
+                                            .into(),
+                                        Button::new()
+                                            .height(Size::Fill)
+                                            .padding((4.0, 8.0))
+                                            .corner_radius(4.0)
+                                            .icon(freya_icons::lucide::upload())
+                                            .text("EXPORT")
+                                            .on_press(move |_| export(radio))
+                                            .into(),
+                                    ]),
+                            )
+                            .child(if annotations.read().is_empty() {
+                                rect()
+                                    .width(Size::Fill)
+                                    .height(Size::px(56.0))
+                                    .center()
+                                    .child(
+                                        label()
+                                            .color(Color::from_hex(colors::TEXT).unwrap())
+                                            .text("No annotations yet, place one on the map"),
+                                    )
+                                    .into_element()
+                            } else {
+                                rect()
+                                    .spacing(4.0)
+                                    .children(
+                                        annotations
+                                            .read()
+                                            .iter()
+                                            .map(|annotation| {
+                                                AnnotationEntry {
+                                                    annotation: annotation.clone(),
+                                                    map_size: *map_size.read(),
+                                                }
+                                                .into()
+                                            })
+                                            .collect::<Vec<Element>>(),
+                                    )
+                                    .into_element()
+                            }),
+                    ),
+            )
+    }
+}
+
+fn export(radio: Radio<Data, DataChannel>) {
+    let server_name = radio
+        .read()
+        .selected_server
+        .as_ref()
+        .map(|server| server.name.clone());
+    let export = AnnotationExport::new(
+        radio.read().annotations.clone(),
+        server_name.clone(),
+        radio.read().info_state.map_size,
+    );
+    let filename = format!(
+        "{}_annotations.json",
+        server_name.unwrap_or_else(|| "server".to_string())
+    );
+
+    spawn(async move {
+        let path = blocking::unblock(move || {
+            rfd::FileDialog::new()
+                .add_filter("Annotations", &["json"])
+                .set_file_name(filename)
+                .save_file()
+        })
+        .await;
+        let Some(path) = path else {
+            return;
+        };
+
+        let toasts = radio.slice_mut(DataChannel::ToastsUpdate, |s| &mut s.toasts);
+        match export_annotations(&path, &export) {
+            Ok(_) => create_toast(
+                toasts.into_writable(),
+                "Annotations exported".to_string(),
+                format!("Saved {} annotations", export.annotations.len()),
+                Timeout::Default,
+                None::<fn(())>,
+            ),
+            Err(err) => {
+                create_toast(
+                    toasts.into_writable(),
+                    "Annotations error".to_string(),
+                    "Error exporting annotations".to_string(),
+                    Timeout::Default,
+                    None::<fn(())>,
+                );
+                eprintln!("Error exporting annotations: {:?}", err);
+            }
+        }
+    });
+}
+
+/// Merging keeps the current annotations, replacing drops them in favour of the file.
+fn import(radio: Radio<Data, DataChannel>, mode: ImportMode) {
+    spawn(async move {
+        let path = blocking::unblock(move || {
+            rfd::FileDialog::new()
+                .add_filter("Annotations", &["json"])
+                .pick_file()
+        })
+        .await;
+        let Some(path) = path else {
+            return;
+        };
+
+        let toasts = radio.slice_mut(DataChannel::ToastsUpdate, |s| &mut s.toasts);
+        let map_size = radio.read().info_state.map_size;
+        let imported = match import_annotations(&path, map_size) {
+            Ok(export) => export.annotations,
+            Err(err) => {
+                create_toast(
+                    toasts.into_writable(),
+                    "Annotations error".to_string(),
+                    err.to_string(),
+                    Timeout::Default,
+                    None::<fn(())>,
+                );
+                eprintln!("Error importing annotations: {:?}", err);
+                return;
+            }
+        };
+
+        let count = imported.len();
+        let annotations = match mode {
+            ImportMode::Merge => {
+                let mut annotations = radio.read().annotations.clone();
+                merge_annotations(&mut annotations, imported);
+                annotations
+            }
+            ImportMode::Replace => imported,
+        };
+        if let Some(state_tx) = &radio.read().state_tx {
+            let _ = state_tx.unbounded_send(ChannelSend::AnnotationsUpdate(annotations));
+        }
+        create_toast(
+            toasts.into_writable(),
+            "Annotations imported".to_string(),
+            format!("Imported {} annotations", count),
+            Timeout::Default,
+            None::<fn(())>,
+        );
+    });
+}
+
+#[derive(PartialEq)]
+struct AnnotationEntry {
+    annotation: Annotation,
+    map_size: Option<u32>,
+}
+
+impl Component for AnnotationEntry {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::NoUpdate);
+        let mut editing = use_state(|| false);
+        let label_text = use_state(|| self.annotation.label.clone());
+        let note_text = use_state(|| self.annotation.note.clone());
+
+        // Every change goes through the main loop, which also persists the list
+        let id = self.annotation.id;
+        let update = move |change: &dyn Fn(&mut Annotation)| {
+            let mut annotations = radio.read().annotations.clone();
+            annotations
+                .iter_mut()
+                .filter(|annotation| annotation.id == id)
+                .for_each(change);
+            if let Some(state_tx) = &radio.read().state_tx {
+                let _ = state_tx.unbounded_send(ChannelSend::AnnotationsUpdate(annotations));
+            }
+        };
+
+        let (color, _) = index_to_color(self.annotation.color);
+        let (x, y) = self.annotation.shape.anchor();
+        let description = match self.map_size {
+            Some(map_size) => format!(
+                "{} {}",
+                self.annotation.shape.tool().title(),
+                grid_reference(x, y, map_size as f32)
+            ),
+            None => self.annotation.shape.tool().title().to_string(),
+        };
+
+        rect()
+            .width(Size::Fill)
+            .padding(8.0)
+            .spacing(8.0)
+            .background(
+                Color::from_hex(colors::BACKGROUND_DARK)
+                    .unwrap()
+                    .with_a(128),
+            )
+            .child(
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::px(40.0))
+                    .spacing(4.0)
+                    .direction(Direction::Horizontal)
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .children([
+                        svg(index_to_icon(self.annotation.icon))
+                            .width(Size::px(24.0))
+                            .height(Size::px(24.0))
+                            .color(color)
+                            .into(),
+                        rect()
+                            .width(Size::flex(1.0))
+                            .padding((0.0, 4.0))
+                            .spacing(2.0)
+                            .children([
+                                label()
+                                    .font_size(16.0)
+                                    .font_weight(FontWeight::BOLD)
+                                    .color(Color::from_hex(colors::TEXT).unwrap())
+                                    .max_lines(1)
+                                    .text(self.annotation.label.clone())
+                                    .into(),
+                                label()
+                                    .font_size(12.0)
+                                    .font_weight(FontWeight::BOLD)
+                                    .color(Color::from_hex(colors::ICON).unwrap())
+                                    .max_lines(1)
+                                    .text(if self.annotation.note.is_empty() {
+                                        description
+                                    } else {
+                                        format!("{}  {}", description, self.annotation.note)
+                                    })
+                                    .into(),
+                            ])
+                            .into(),
+                        Button::new()
+                            .height(Size::Fill)
+                            .icon(freya_icons::lucide::palette())
+                            .icon_color(color)
+                            .on_press(move |_| {
+                                update(&|annotation| {
+                                    annotation.color = (annotation.color + 1) % ANNOTATION_COLORS;
+                                });
+                            })
+                            .into(),
+                        Button::new()
+                            .height(Size::Fill)
+                            .icon(index_to_icon(self.annotation.icon))
+                            .on_press(move |_| {
+                                update(&|annotation| {
+                                    annotation.icon = (annotation.icon + 1) % ANNOTATION_ICONS;
+                                });
+                            })
+                            .into(),
+                        Button::new()
+                            .height(Size::Fill)
+                            .icon(freya_icons::lucide::pencil())
+                            .on_press(move |_| {
+                                let value = editing();
+                                editing.set(!value);
+                            })
+                            .active(editing())
+                            .into(),
+                        Button::new()
+                            .height(Size::Fill)
+                            .icon(freya_icons::lucide::locate_fixed())
+                            .on_press(move |_| {
+                                radio
+                                    .write_channel(DataChannel::MapSettingsUpdate)
+                                    .settings
+                                    .map_settings
+                                    .center = false;
+                                radio.write_channel(DataChannel::MapFocusUpdate).map_focus =
+                                    Some(Point2D::new(x, y));
+                                RouterContext::get().replace(Route::Map);
+                            })
+                            .into(),
+                        Button::new()
+                            .height(Size::Fill)
+                            .icon(freya_icons::lucide::trash_2())
+                            .on_press(move |_| {
+                                let mut annotations = radio.read().annotations.clone();
+                                annotations.retain(|annotation| annotation.id != id);
+                                if let Some(state_tx) = &radio.read().state_tx {
+                                    let _ = state_tx.unbounded_send(
+                                        ChannelSend::AnnotationsUpdate(annotations),
+                                    );
+                                }
+                            })
+                            .into(),
+                    ]),
+            )
+            .maybe_child(editing().then(|| {
+                rect()
+                    .width(Size::Fill)
+                    .spacing(4.0)
+                    .direction(Direction::Horizontal)
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .children([
+                        Input::new(label_text)
+                            .width(Size::flex(1.0))
+                            .placeholder("Label")
+                            .into(),
+                        Input::new(note_text)
+                            .width(Size::flex(2.0))
+                            .placeholder("Note")
+                            .into(),
+                        Button::new()
+                            .height(Size::px(32.0))
+                            .padding((4.0, 8.0))
+                            .corner_radius(4.0)
+                            .icon(freya_icons::lucide::save())
+                            .text("SAVE")
+                            .on_press(move |_| {
+                                let label = label_text.read().trim().to_string();
+                                let note = note_text.read().trim().to_string();
+                                update(&|annotation| {
+                                    annotation.label = label.clone();
+                                    annotation.note = note.clone();
+                                });
+                                editing.set(false);
+                            })
+                            .into(),
+                    ])
+            }))
+    }
+}
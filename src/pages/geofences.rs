@@ -0,0 +1,242 @@
+use euclid::Point2D;
+use freya::{prelude::*, radio::use_radio};
+use freya_router::prelude::RouterContext;
+
+use crate::{
+    ChannelSend, Data, DataChannel,
+    app::Route,
+    colors,
+    components::Button,
+    utils::{Geofence, GeofenceDraft, GeofenceKind, GeofenceShape, SpawnEvent},
+};
+
+#[derive(PartialEq)]
+pub struct GeofencesPage {}
+
+impl Component for GeofencesPage {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::GeofencesUpdate);
+        let geofences = radio.slice_current(|s| &s.geofences);
+
+        let mut start_drawing = move |kind: GeofenceKind| {
+            radio.write_channel(DataChannel::GeofenceDraftUpdate).geofence_draft =
+                Some(GeofenceDraft::new(kind));
+            radio
+                .write_channel(DataChannel::MapSettingsUpdate)
+                .settings
+                .map_settings
+                .geofences = true;
+            RouterContext::get().replace(Route::Map);
+        };
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::Fill)
+            .background(Color::from_hex(colors::BACKGROUND).unwrap())
+            .corner_radius(8.0)
+            .child(
+                ScrollView::new().width(Size::Fill).height(Size::Fill).child(
+                    rect()
+                        .padding(8.0)
+                        .spacing(4.0)
+                        .child(
+                            rect()
+                                .width(Size::Fill)
+                                .height(Size::px(48.0))
+                                .padding(8.0)
+                                .spacing(8.0)
+                                .direction(Direction::Horizontal)
+                                .cross_align(Alignment::Center)
+                                .content(Content::Flex)
+                                .children([
+                                    svg(freya_icons::lucide::shield())
+                                        .height(Size::Fill)
+                                        .color(Color::from_hex(colors::SELECT).unwrap())
+                                        .into(),
+                                    label()
+                                        .width(Size::flex(1.0))
+                                        .font_size(24.0)
+                                        .font_weight(FontWeight::BOLD)
+                                        .color(Color::from_hex(colors::TEXT).unwrap())
+                                        .text("GEOFENCES")
+                                        .into(),
+                                    Button::new()
+                                        .height(Size::Fill)
+                                        .padding((4.0, 8.0))
+                                        .corner_radius(4.0)
+                                        .icon(freya_icons::lucide::circle())
+                                        .text("NEW CIRCLE")
+                                        .on_press(move |_| start_drawing(GeofenceKind::Circle))
+                                        .into(),
+                                    Button::new()
+                                        .height(Size::Fill)
+                                        .padding((4.0, 8.0))
+                                        .corner_radius(4.0)
+                                        .icon(freya_icons::lucide::square())
+                                        .text("NEW RECTANGLE")
+                                        .on_press(move |_| start_drawing(GeofenceKind::Rectangle))
+                                        .into(),
+                                ]),
+                        )
+                        .child(if geofences.read().is_empty() {
+                            rect()
+                                .width(Size::Fill)
+                                .height(Size::px(56.0))
+                                .center()
+                                .child(
+                                    label()
+                                        .color(Color::from_hex(colors::TEXT).unwrap())
+                                        .text("No geofences yet, draw one on the map"),
+                                )
+                                .into_element()
+                        } else {
+                            rect()
+                                .spacing(4.0)
+                                .children(
+                                    geofences
+                                        .read()
+                                        .iter()
+                                        .map(|geofence| {
+                                            GeofenceEntry {
+                                                geofence: geofence.clone(),
+                                            }
+                                            .into()
+                                        })
+                                        .collect::<Vec<Element>>(),
+                                )
+                                .into_element()
+                        }),
+                ),
+            )
+    }
+}
+
+#[derive(PartialEq)]
+struct GeofenceEntry {
+    geofence: Geofence,
+}
+
+impl Component for GeofenceEntry {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::NoUpdate);
+
+        // Every change goes through the main loop, which also persists the list
+        let update = move |change: &dyn Fn(&mut Vec<Geofence>)| {
+            let mut geofences = radio.read().geofences.clone();
+            change(&mut geofences);
+            if let Some(state_tx) = &radio.read().state_tx {
+                let _ = state_tx.unbounded_send(ChannelSend::GeofencesUpdate(geofences));
+            }
+        };
+
+        let id = self.geofence.id;
+        let description = match self.geofence.shape {
+            GeofenceShape::Circle { radius, .. } => format!("CIRCLE {:.0}m", radius * 2.0),
+            GeofenceShape::Rectangle {
+                left,
+                bottom,
+                right,
+                top,
+            } => format!("RECTANGLE {:.0}x{:.0}m", right - left, top - bottom),
+        };
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::px(56.0))
+            .padding(8.0)
+            .spacing(4.0)
+            .direction(Direction::Horizontal)
+            .cross_align(Alignment::Center)
+            .content(Content::Flex)
+            .background(Color::from_hex(colors::BACKGROUND_DARK).unwrap().with_a(128))
+            .children([
+                rect()
+                    .width(Size::flex(1.0))
+                    .spacing(2.0)
+                    .children([
+                        label()
+                            .font_size(16.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::TEXT).unwrap())
+                            .text(self.geofence.name.clone())
+                            .into(),
+                        label()
+                            .font_size(12.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::ICON).unwrap())
+                            .text(description)
+                            .into(),
+                    ])
+                    .into(),
+                Button::new()
+                    .height(Size::Fill)
+                    .padding((4.0, 8.0))
+                    .corner_radius(4.0)
+                    .text("TEAM")
+                    .on_press(move |_| {
+                        update(&|geofences| {
+                            for geofence in geofences.iter_mut().filter(|g| g.id == id) {
+                                geofence.team = !geofence.team;
+                            }
+                        });
+                    })
+                    .active(self.geofence.team)
+                    .into(),
+            ])
+            .children(
+                SpawnEvent::ALL
+                    .into_iter()
+                    .map(|event| {
+                        Button::new()
+                            .height(Size::Fill)
+                            .padding((4.0, 8.0))
+                            .corner_radius(4.0)
+                            .text(event.title())
+                            .on_press(move |_| {
+                                update(&|geofences| {
+                                    for geofence in geofences.iter_mut().filter(|g| g.id == id) {
+                                        if geofence.markers.contains(&event) {
+                                            geofence.markers.retain(|marker| *marker != event);
+                                        } else {
+                                            geofence.markers.push(event);
+                                        }
+                                    }
+                                });
+                            })
+                            .active(self.geofence.markers.contains(&event))
+                            .into()
+                    })
+                    .collect::<Vec<Element>>(),
+            )
+            .children([
+                Button::new()
+                    .height(Size::Fill)
+                    .padding((4.0, 8.0))
+                    .corner_radius(4.0)
+                    .icon(freya_icons::lucide::locate_fixed())
+                    .on_press({
+                        let (x, y) = self.geofence.shape.center();
+                        move |_| {
+                            radio
+                                .write_channel(DataChannel::MapSettingsUpdate)
+                                .settings
+                                .map_settings
+                                .center = false;
+                            radio.write_channel(DataChannel::MapFocusUpdate).map_focus =
+                                Some(Point2D::new(x, y));
+                            RouterContext::get().replace(Route::Map);
+                        }
+                    })
+                    .into(),
+                Button::new()
+                    .height(Size::Fill)
+                    .padding((4.0, 8.0))
+                    .corner_radius(4.0)
+                    .icon(freya_icons::lucide::trash_2())
+                    .on_press(move |_| {
+                        update(&|geofences| geofences.retain(|g| g.id != id));
+                    })
+                    .into(),
+            ])
+    }
+}
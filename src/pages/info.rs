@@ -4,7 +4,7 @@ use timeago::Formatter;
 
 use crate::{
     Data, DataChannel, colors,
-    components::{CachedImage, PlayerCard},
+    components::{Button, CachedImage, EventTimers, PlayerCard, PopulationChart},
 };
 
 #[derive(PartialEq)]
@@ -86,6 +86,7 @@ impl Component for Info {
                                     Bytes::from_static(include_bytes!("../assets/MDI/map.svg")),
                                 )
                                 .into(),
+                                EventTimers::new().into(),
                             ]),
                     )
                     .into(),
@@ -360,6 +361,7 @@ impl Component for Info {
                                         },
                                     )
                                     .into(),
+                                PopulationPanel {}.into(),
                             ])
                             .into(),
                     ])
@@ -368,6 +370,71 @@ impl Component for Info {
     }
 }
 
+#[derive(PartialEq)]
+struct PopulationPanel {}
+
+impl Component for PopulationPanel {
+    fn render(&self) -> impl IntoElement {
+        let radio = use_radio::<Data, DataChannel>(DataChannel::PopulationUpdate);
+        let population = radio.slice_current(|s| &s.population);
+
+        let mut week = use_state(|| false);
+
+        rect()
+            .width(Size::flex(1.0))
+            .height(Size::Fill)
+            .padding(8.0)
+            .spacing(8.0)
+            .background(Color::from_hex(colors::BACKGROUND).unwrap())
+            .corner_radius(8.0)
+            .content(Content::Flex)
+            .children([
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::px(30.0))
+                    .spacing(4.0)
+                    .direction(Direction::Horizontal)
+                    .cross_align(Alignment::Center)
+                    .content(Content::Flex)
+                    .children([
+                        label()
+                            .width(Size::flex(1.0))
+                            .font_size(16.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::TEXT).unwrap())
+                            .text("POPULATION")
+                            .into(),
+                        Button::new()
+                            .height(Size::Fill)
+                            .padding((4.0, 8.0))
+                            .corner_radius(4.0)
+                            .text("DAY")
+                            .on_press(move |_| week.set(false))
+                            .active(!week())
+                            .into(),
+                        Button::new()
+                            .height(Size::Fill)
+                            .padding((4.0, 8.0))
+                            .corner_radius(4.0)
+                            .text("WEEK")
+                            .on_press(move |_| week.set(true))
+                            .active(week())
+                            .into(),
+                    ])
+                    .into(),
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::flex(1.0))
+                    .child(PopulationChart::new(population.into_readable()).range(if week() {
+                        7 * 24 * 60 * 60
+                    } else {
+                        24 * 60 * 60
+                    }))
+                    .into(),
+            ])
+    }
+}
+
 #[derive(PartialEq)]
 struct InfoCard {
     title: String,
@@ -9,6 +9,7 @@ use crate::{
     app::Route,
     colors,
     components::{Modal, ModalType, Timeout},
+    pages::NotificationKind,
     utils::*,
 };
 
@@ -132,28 +133,31 @@ impl Component for Loading {
                                         Ok(mut data) => {
                                             data.desc = data.desc.replace("\\n", "\n");
                                             state_tx
-                                                .unbounded_send(ChannelSend::AddToast(ToastData {
-                                                    title: data.name.clone(),
-                                                    message: "Click here to see pairing request"
-                                                        .to_string(),
-                                                    timeout: Timeout::Infinite,
-                                                    on_press: Some(Box::new({
-                                                        let state_tx = state_tx.clone();
-                                                        move |_| {
-                                                            state_tx
-                                                                .unbounded_send(
-                                                                    ChannelSend::ModalUpdate(Some(
-                                                                        Modal::new(
-                                                                            ModalType::ServerPair(
-                                                                                data.clone(),
-                                                                            ),
+                                                .unbounded_send(ChannelSend::Notification(
+                                                    NotificationKind::Pairing,
+                                                    ToastData {
+                                                        title: data.name.clone(),
+                                                        message: "Click here to see pairing request"
+                                                            .to_string(),
+                                                        timeout: Timeout::Infinite,
+                                                        on_press: Some(Box::new({
+                                                            let state_tx = state_tx.clone();
+                                                            move |_| {
+                                                                state_tx
+                                                                    .unbounded_send(
+                                                                        ChannelSend::ModalUpdate(
+                                                                            Some(Modal::new(
+                                                                                ModalType::ServerPair(
+                                                                                    data.clone(),
+                                                                                ),
+                                                                            )),
                                                                         ),
-                                                                    )),
-                                                                )
-                                                                .unwrap();
-                                                        }
-                                                    })),
-                                                }))
+                                                                    )
+                                                                    .unwrap();
+                                                            }
+                                                        })),
+                                                    },
+                                                ))
                                                 .unwrap();
                                         }
                                         Err(err) => {
@@ -166,16 +170,19 @@ impl Component for Loading {
                                     match team_data {
                                         Ok(data) => {
                                             state_tx
-                                                .unbounded_send(ChannelSend::AddToast(ToastData {
-                                                    title: message.unwrap_or_else(|| {
-                                                        "Unknown server".to_string()
-                                                    }),
-                                                    message: title.unwrap_or_else(|| {
-                                                        "Empty team message".to_string()
-                                                    }),
-                                                    timeout: Timeout::Custom(15),
-                                                    on_press: None,
-                                                }))
+                                                .unbounded_send(ChannelSend::Notification(
+                                                    NotificationKind::Team,
+                                                    ToastData {
+                                                        title: message.unwrap_or_else(|| {
+                                                            "Unknown server".to_string()
+                                                        }),
+                                                        message: title.unwrap_or_else(|| {
+                                                            "Empty team message".to_string()
+                                                        }),
+                                                        timeout: Timeout::Custom(15),
+                                                        on_press: None,
+                                                    },
+                                                ))
                                                 .unwrap();
                                         }
                                         Err(err) => {
@@ -188,16 +195,19 @@ impl Component for Loading {
                                     match player_data {
                                         Ok(data) => {
                                             state_tx
-                                                .unbounded_send(ChannelSend::AddToast(ToastData {
-                                                    title: message.unwrap_or_else(|| {
-                                                        "Unknown server".to_string()
-                                                    }),
-                                                    message: title.unwrap_or_else(|| {
-                                                        "Empty player message".to_string()
-                                                    }),
-                                                    timeout: Timeout::Custom(15),
-                                                    on_press: None,
-                                                }))
+                                                .unbounded_send(ChannelSend::Notification(
+                                                    NotificationKind::Team,
+                                                    ToastData {
+                                                        title: message.unwrap_or_else(|| {
+                                                            "Unknown server".to_string()
+                                                        }),
+                                                        message: title.unwrap_or_else(|| {
+                                                            "Empty player message".to_string()
+                                                        }),
+                                                        timeout: Timeout::Custom(15),
+                                                        on_press: None,
+                                                    },
+                                                ))
                                                 .unwrap();
                                         }
                                         Err(err) => {
@@ -205,6 +215,39 @@ impl Component for Loading {
                                         }
                                     }
                                 }
+                                "alarm" => {
+                                    let alarm_data = serde_json::from_str::<AlarmData>(&body);
+                                    match alarm_data {
+                                        Ok(data) => {
+                                            let title = title
+                                                .unwrap_or_else(|| "Alarm".to_string());
+                                            let message = message.unwrap_or_default();
+                                            state_tx
+                                                .unbounded_send(ChannelSend::Notification(
+                                                    NotificationKind::Alarm,
+                                                    ToastData {
+                                                        title: title.clone(),
+                                                        message: message.clone(),
+                                                        timeout: Timeout::Custom(15),
+                                                        on_press: None,
+                                                    },
+                                                ))
+                                                .unwrap();
+                                            state_tx
+                                                .unbounded_send(ChannelSend::AddTimelineEvent(
+                                                    data.id,
+                                                    TimelineEvent::new(TimelineEventKind::Alarm {
+                                                        title,
+                                                        message,
+                                                    }),
+                                                ))
+                                                .unwrap();
+                                        }
+                                        Err(err) => {
+                                            println!("Error parsing alarm data: {}", err);
+                                        }
+                                    }
+                                }
                                 data => {
                                     println!("Unknown channel ID: {}", channel_id);
                                     let mut file = OpenOptions::new()
@@ -458,3 +501,12 @@ struct PlayerData {
     #[serde(rename = "targetName")]
     target_name: String,
 }
+
+#[derive(Deserialize, Serialize, Debug)]
+#[allow(dead_code)]
+struct AlarmData {
+    id: String,
+    name: String,
+    ip: String,
+    port: String,
+}
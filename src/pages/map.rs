@@ -1,6 +1,26 @@
-use freya::{prelude::*, radio::use_radio};
+use std::time::Duration;
 
-use crate::{Data, DataChannel, components::Map as MapComponent};
+use chrono::{DateTime, Local};
+use euclid::Point2D;
+use freya::{
+    prelude::*,
+    radio::{Radio, use_radio},
+};
+use freya_router::prelude::RouterContext;
+use rand::Rng;
+
+use crate::{
+    ChannelSend, Data, DataChannel,
+    app::Route,
+    colors,
+    components::{Button, DrawEvent, Map as MapComponent, Slider},
+    utils::{
+        Annotation, AnnotationShape, AnnotationTool, Geofence, GeofenceShape, TRAVEL_MODES,
+        format_distance, format_travel_time, grid_reference, route_segments,
+    },
+};
+
+const PLAYBACK_SPEEDS: [i64; 4] = [1, 10, 60, 300];
 
 #[derive(Clone)]
 pub struct MapSettings {
@@ -10,6 +30,13 @@ pub struct MapSettings {
     pub deaths: bool,
     pub monuments: bool,
     pub shops: bool,
+    pub zones: bool,
+    pub trails: bool,
+    pub trail_minutes: f32,
+    pub heatmap: bool,
+    pub heatmap_member: Option<u64>,
+    pub geofences: bool,
+    pub annotations: bool,
 }
 
 impl Default for MapSettings {
@@ -21,6 +48,31 @@ impl Default for MapSettings {
             deaths: true,
             monuments: true,
             shops: true,
+            zones: true,
+            trails: false,
+            trail_minutes: 10.0,
+            heatmap: false,
+            heatmap_member: None,
+            geofences: true,
+            annotations: true,
+        }
+    }
+}
+
+/// Session playback state. `time` is `None` while the map shows live data.
+#[derive(Clone)]
+pub struct Playback {
+    pub time: Option<i64>,
+    pub playing: bool,
+    pub speed: i64,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            time: None,
+            playing: false,
+            speed: 10,
         }
     }
 }
@@ -30,25 +82,557 @@ pub struct Map {}
 
 impl Component for Map {
     fn render(&self) -> impl IntoElement {
-        let radio = use_radio::<Data, DataChannel>(DataChannel::MapSettingsUpdate);
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::MapSettingsUpdate);
         let grid = radio.slice_current(|s| &s.settings.map_settings.grid);
         let markers = radio.slice_current(|s| &s.settings.map_settings.markers);
         let deaths = radio.slice_current(|s| &s.settings.map_settings.deaths);
         let monuments = radio.slice_current(|s| &s.settings.map_settings.monuments);
         let shops = radio.slice_current(|s| &s.settings.map_settings.shops);
+        let zones = radio.slice_current(|s| &s.settings.map_settings.zones);
+        let trails = radio.slice_current(|s| &s.settings.map_settings.trails);
+        let trail_minutes = radio.slice_current(|s| &s.settings.map_settings.trail_minutes);
+        let heatmap = radio.slice_current(|s| &s.settings.map_settings.heatmap);
+        let heatmap_member = radio.slice_current(|s| &s.settings.map_settings.heatmap_member);
+        let geofences = radio.slice_current(|s| &s.settings.map_settings.geofences);
+        let annotations = radio.slice_current(|s| &s.settings.map_settings.annotations);
         let center = radio.slice_current(|s| &s.settings.map_settings.center);
+        let geofence_draft = radio.slice(DataChannel::GeofenceDraftUpdate, |s| &s.geofence_draft);
+        let annotation_draft =
+            radio.slice(DataChannel::AnnotationDraftUpdate, |s| &s.annotation_draft);
+        let measure_route = radio.slice(DataChannel::MeasureUpdate, |s| &s.measure_route);
+        let history = radio.slice(DataChannel::HistoryUpdate, |s| &s.history);
+        let playback = radio.slice(DataChannel::PlaybackUpdate, |s| &s.playback);
 
         let zoom: State<f32> = use_state(|| 1.0);
 
-        rect().overflow(Overflow::Clip).corner_radius(8.0).child(
-            MapComponent::new()
-                .grid(grid.into_readable())
-                .markers(markers.into_readable())
-                .deaths(deaths.into_readable())
-                .monuments(monuments.into_readable())
-                .shops(shops.into_readable())
-                .zoom(zoom)
-                .center(center.into_readable()),
-        )
+        let snapshot = playback.read().time.and_then(|time| {
+            let history = history.read();
+            history
+                .iter()
+                .rev()
+                .find(|snapshot| snapshot.timestamp <= time)
+                .or_else(|| history.first())
+                .cloned()
+        });
+
+        // Drawing turns map dragging into drawing until the draft is finished or cancelled
+        let drawing = geofence_draft.read().is_some() || annotation_draft.read().is_some();
+        let on_draw = drawing.then(|| {
+            EventHandler::new(move |event: DrawEvent| {
+                if radio.read().annotation_draft.is_some() {
+                    place_annotation_point(radio, event);
+                    return;
+                }
+                let Some(mut draft) = radio.read().geofence_draft else {
+                    return;
+                };
+                let point = (event.point().x, event.point().y);
+                match event {
+                    DrawEvent::Start(_) => {
+                        draft.start = Some(point);
+                        draft.end = Some(point);
+                    }
+                    DrawEvent::Move(_) | DrawEvent::End(_) => draft.end = Some(point),
+                }
+
+                if !matches!(event, DrawEvent::End(_)) {
+                    radio
+                        .write_channel(DataChannel::GeofenceDraftUpdate)
+                        .geofence_draft = Some(draft);
+                    return;
+                }
+
+                radio
+                    .write_channel(DataChannel::GeofenceDraftUpdate)
+                    .geofence_draft = None;
+                let Some(shape) = draft.shape().filter(|shape| !shape.is_degenerate()) else {
+                    return;
+                };
+
+                let mut geofences = radio.read().geofences.clone();
+                geofences.push(new_geofence(
+                    shape,
+                    radio.read().info_state.map_size,
+                    geofences.len(),
+                ));
+                if let Some(state_tx) = &radio.read().state_tx {
+                    let _ = state_tx.unbounded_send(ChannelSend::GeofencesUpdate(geofences));
+                }
+                RouterContext::get().replace(Route::GeofencesPage);
+            })
+        });
+
+        let measuring = !drawing && measure_route.read().is_some();
+        let on_click = measuring.then(|| {
+            EventHandler::new(move |point: Point2D<f32, ()>| {
+                if let Some(route) = &mut radio
+                    .write_channel(DataChannel::MeasureUpdate)
+                    .measure_route
+                {
+                    route.push((point.x, point.y));
+                }
+            })
+        });
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::Fill)
+            .spacing(8.0)
+            .content(Content::Flex)
+            .children([
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::flex(1.0))
+                    .overflow(Overflow::Clip)
+                    .corner_radius(8.0)
+                    .child(
+                        MapComponent::new()
+                            .grid(grid.into_readable())
+                            .markers(markers.into_readable())
+                            .deaths(deaths.into_readable())
+                            .monuments(monuments.into_readable())
+                            .shops(shops.into_readable())
+                            .zones(zones.into_readable())
+                            .trails(trails.into_readable())
+                            .trail_minutes(trail_minutes.into_readable())
+                            .heatmap(heatmap.into_readable())
+                            .heatmap_member(heatmap_member.into_readable())
+                            .geofences(geofences.into_readable())
+                            .annotations(annotations.into_readable())
+                            .on_draw(on_draw)
+                            .on_click(on_click)
+                            .zoom(zoom)
+                            .center(center.into_readable())
+                            .snapshot(snapshot),
+                    )
+                    .into(),
+                if drawing {
+                    DraftBar {}.into()
+                } else if measuring {
+                    MeasureBar {}.into()
+                } else {
+                    PlaybackBar {}.into()
+                },
+            ])
+    }
+}
+
+fn new_geofence(shape: GeofenceShape, map_size: Option<u32>, count: usize) -> Geofence {
+    let (x, y) = shape.center();
+    Geofence {
+        id: rand::rng().next_u64(),
+        name: match map_size {
+            Some(map_size) => format!("ZONE {}", grid_reference(x, y, map_size as f32)),
+            None => format!("ZONE {}", count + 1),
+        },
+        shape,
+        team: true,
+        markers: Vec::new(),
+    }
+}
+
+/// Pins are placed on the first click, lines and polygons collect a point per click.
+fn place_annotation_point(mut radio: Radio<Data, DataChannel>, event: DrawEvent) {
+    let DrawEvent::End(point) = event else {
+        return;
+    };
+    let Some(mut draft) = radio.read().annotation_draft.clone() else {
+        return;
+    };
+    draft.points.push((point.x, point.y));
+
+    if draft.tool.min_points() == 1 {
+        finish_annotation(radio, draft.tool, draft.points);
+    } else {
+        radio
+            .write_channel(DataChannel::AnnotationDraftUpdate)
+            .annotation_draft = Some(draft);
+    }
+}
+
+fn finish_annotation(
+    mut radio: Radio<Data, DataChannel>,
+    tool: AnnotationTool,
+    points: Vec<(f32, f32)>,
+) {
+    radio
+        .write_channel(DataChannel::AnnotationDraftUpdate)
+        .annotation_draft = None;
+    let Some(shape) = AnnotationShape::from_points(tool, points) else {
+        return;
+    };
+
+    let mut annotations = radio.read().annotations.clone();
+    annotations.push(new_annotation(shape, radio.read().info_state.map_size));
+    if let Some(state_tx) = &radio.read().state_tx {
+        let _ = state_tx.unbounded_send(ChannelSend::AnnotationsUpdate(annotations));
+    }
+    RouterContext::get().replace(Route::AnnotationsPage);
+}
+
+fn new_annotation(shape: AnnotationShape, map_size: Option<u32>) -> Annotation {
+    let (x, y) = shape.anchor();
+    Annotation {
+        id: rand::rng().next_u64(),
+        label: match map_size {
+            Some(map_size) => format!(
+                "{} {}",
+                shape.tool().title(),
+                grid_reference(x, y, map_size as f32)
+            ),
+            None => shape.tool().title().to_string(),
+        },
+        note: String::new(),
+        icon: 0,
+        color: 0,
+        shape,
+    }
+}
+
+/// Replaces the playback bar while something is being drawn on the map.
+#[derive(PartialEq)]
+struct DraftBar {}
+
+impl Component for DraftBar {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::AnnotationDraftUpdate);
+        let geofence_draft = radio.slice(DataChannel::GeofenceDraftUpdate, |s| &s.geofence_draft);
+
+        let annotation_draft = radio.read().annotation_draft.clone();
+        let hint = match &annotation_draft {
+            Some(draft) if draft.tool == AnnotationTool::Pin => {
+                "Click on the map to place the pin".to_string()
+            }
+            Some(draft) => format!(
+                "Click on the map to add points, {} placed",
+                draft.points.len()
+            ),
+            None if geofence_draft.read().is_some() => {
+                "Drag on the map to draw the geofence".to_string()
+            }
+            None => String::new(),
+        };
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::px(40.0))
+            .spacing(4.0)
+            .direction(Direction::Horizontal)
+            .cross_align(Alignment::Center)
+            .content(Content::Flex)
+            .children([
+                rect()
+                    .width(Size::flex(1.0))
+                    .height(Size::Fill)
+                    .padding((0.0, 12.0))
+                    .corner_radius(8.0)
+                    .main_align(Alignment::Center)
+                    .background(Color::from_hex(colors::BACKGROUND).unwrap())
+                    .child(
+                        label()
+                            .font_size(16.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::TEXT).unwrap())
+                            .text(hint),
+                    )
+                    .into(),
+                Button::new()
+                    .width(Size::px(100.0))
+                    .height(Size::Fill)
+                    .align(Alignment::Center)
+                    .icon(freya_icons::lucide::check())
+                    .text("FINISH")
+                    .on_press(move |_| {
+                        let Some(draft) = radio.read().annotation_draft.clone() else {
+                            return;
+                        };
+                        if draft.can_finish() {
+                            finish_annotation(radio, draft.tool, draft.points);
+                        }
+                    })
+                    .active(
+                        annotation_draft
+                            .as_ref()
+                            .is_some_and(|draft| draft.can_finish()),
+                    )
+                    .into(),
+                Button::new()
+                    .width(Size::px(100.0))
+                    .height(Size::Fill)
+                    .align(Alignment::Center)
+                    .icon(freya_icons::lucide::x())
+                    .text("CANCEL")
+                    .on_press(move |_| {
+                        radio
+                            .write_channel(DataChannel::AnnotationDraftUpdate)
+                            .annotation_draft = None;
+                        radio
+                            .write_channel(DataChannel::GeofenceDraftUpdate)
+                            .geofence_draft = None;
+                    })
+                    .into(),
+            ])
+    }
+}
+
+/// Replaces the playback bar while the measuring tool is on.
+#[derive(PartialEq)]
+struct MeasureBar {}
+
+impl Component for MeasureBar {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::MeasureUpdate);
+
+        let route = radio.read().measure_route.clone().unwrap_or_default();
+        let segments = route_segments(&route);
+        let total: f32 = segments.iter().sum();
+
+        let summary = if segments.is_empty() {
+            "Click on the map to start a route".to_string()
+        } else {
+            let legs = segments
+                .iter()
+                .map(|segment| format_distance(*segment))
+                .collect::<Vec<String>>()
+                .join(" + ");
+            if segments.len() > 1 {
+                format!("{} = {}", legs, format_distance(total))
+            } else {
+                legs
+            }
+        };
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::px(40.0))
+            .spacing(4.0)
+            .direction(Direction::Horizontal)
+            .cross_align(Alignment::Center)
+            .content(Content::Flex)
+            .child(
+                rect()
+                    .width(Size::flex(1.0))
+                    .height(Size::Fill)
+                    .padding((0.0, 12.0))
+                    .corner_radius(8.0)
+                    .main_align(Alignment::Center)
+                    .background(Color::from_hex(colors::BACKGROUND).unwrap())
+                    .child(
+                        label()
+                            .font_size(16.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::TEXT).unwrap())
+                            .max_lines(1)
+                            .text(summary),
+                    ),
+            )
+            .children(
+                TRAVEL_MODES
+                    .into_iter()
+                    .map(|(name, speed)| {
+                        rect()
+                            .width(Size::px(130.0))
+                            .height(Size::Fill)
+                            .corner_radius(8.0)
+                            .main_align(Alignment::Center)
+                            .cross_align(Alignment::Center)
+                            .background(Color::from_hex(colors::BACKGROUND).unwrap())
+                            .children([
+                                label()
+                                    .font_size(10.0)
+                                    .font_weight(FontWeight::BOLD)
+                                    .color(Color::from_hex(colors::ICON).unwrap())
+                                    .text(name)
+                                    .into(),
+                                label()
+                                    .font_size(14.0)
+                                    .font_weight(FontWeight::BOLD)
+                                    .color(Color::from_hex(colors::TEXT).unwrap())
+                                    .text(format_travel_time(total, speed))
+                                    .into(),
+                            ])
+                            .into()
+                    })
+                    .collect::<Vec<Element>>(),
+            )
+            .children([
+                Button::new()
+                    .height(Size::Fill)
+                    .icon(freya_icons::lucide::undo_2())
+                    .on_press(move |_| {
+                        if let Some(route) = &mut radio.write().measure_route {
+                            route.pop();
+                        }
+                    })
+                    .into(),
+                Button::new()
+                    .height(Size::Fill)
+                    .icon(freya_icons::lucide::trash_2())
+                    .on_press(move |_| {
+                        radio.write().measure_route = Some(Vec::new());
+                    })
+                    .into(),
+                Button::new()
+                    .height(Size::Fill)
+                    .icon(freya_icons::lucide::x())
+                    .on_press(move |_| {
+                        radio.write().measure_route = None;
+                    })
+                    .into(),
+            ])
+    }
+}
+
+#[derive(PartialEq)]
+struct PlaybackBar {}
+
+impl Component for PlaybackBar {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::PlaybackUpdate);
+        let history = radio.slice(DataChannel::HistoryUpdate, |s| &s.history);
+
+        use_hook(|| {
+            spawn(async move {
+                loop {
+                    smol::Timer::after(Duration::from_secs(1)).await;
+
+                    let playback = radio.read().playback.clone();
+                    let Some(time) = playback.time else {
+                        continue;
+                    };
+                    if !playback.playing {
+                        continue;
+                    }
+
+                    let end = radio
+                        .read()
+                        .history
+                        .last()
+                        .map(|snapshot| snapshot.timestamp);
+                    let mut data = radio.write();
+                    match end {
+                        Some(end) if time + playback.speed < end => {
+                            data.playback.time = Some(time + playback.speed);
+                        }
+                        // Caught up with the recording, so go back to live data
+                        _ => {
+                            data.playback.time = None;
+                            data.playback.playing = false;
+                        }
+                    }
+                }
+            });
+        });
+
+        let playback = radio.read().playback.clone();
+        let (start, end) = {
+            let history = history.read();
+            match (history.first(), history.last()) {
+                (Some(first), Some(last)) => (first.timestamp, last.timestamp),
+                _ => (0, 0),
+            }
+        };
+        let time = playback.time.unwrap_or(end);
+
+        let time_text = match DateTime::from_timestamp(time, 0) {
+            Some(time) if end > start => time
+                .with_timezone(&Local)
+                .format("%d %b %H:%M:%S")
+                .to_string(),
+            _ => "NO HISTORY".to_string(),
+        };
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::px(40.0))
+            .spacing(4.0)
+            .direction(Direction::Horizontal)
+            .cross_align(Alignment::Center)
+            .content(Content::Flex)
+            .children([
+                Button::new()
+                    .height(Size::Fill)
+                    .icon(if playback.playing {
+                        freya_icons::lucide::pause()
+                    } else {
+                        freya_icons::lucide::play()
+                    })
+                    .on_press(move |_| {
+                        let mut data = radio.write();
+                        if data.playback.playing {
+                            data.playback.playing = false;
+                        } else if end > start {
+                            // Starting from live replays the whole recording
+                            data.playback.time = Some(data.playback.time.unwrap_or(start));
+                            data.playback.playing = true;
+                        }
+                    })
+                    .active(playback.playing)
+                    .into(),
+                Button::new()
+                    .width(Size::px(60.0))
+                    .height(Size::Fill)
+                    .align(Alignment::Center)
+                    .text(format!("{}x", playback.speed))
+                    .on_press(move |_| {
+                        let speed = radio.read().playback.speed;
+                        let index = PLAYBACK_SPEEDS
+                            .iter()
+                            .position(|s| *s == speed)
+                            .unwrap_or(0);
+                        radio.write().playback.speed =
+                            PLAYBACK_SPEEDS[(index + 1) % PLAYBACK_SPEEDS.len()];
+                    })
+                    .into(),
+                rect()
+                    .width(Size::flex(1.0))
+                    .height(Size::Fill)
+                    .padding((0.0, 8.0))
+                    .corner_radius(8.0)
+                    .background(Color::from_hex(colors::BACKGROUND).unwrap())
+                    .child(
+                        // Offsets from the first snapshot keep the values small enough for f32
+                        Slider::new()
+                            .width(Size::Fill)
+                            .show_value(false)
+                            .min(0.0)
+                            .max((end - start).max(1) as f32)
+                            .step(1.0)
+                            .value((time - start) as f32)
+                            .on_change(move |value| {
+                                if end <= start {
+                                    return;
+                                }
+                                radio.write().playback.time = Some(start + value as i64);
+                            }),
+                    )
+                    .into(),
+                rect()
+                    .width(Size::px(150.0))
+                    .height(Size::Fill)
+                    .main_align(Alignment::Center)
+                    .cross_align(Alignment::Center)
+                    .child(
+                        label()
+                            .font_size(16.0)
+                            .font_weight(FontWeight::BOLD)
+                            .color(Color::from_hex(colors::TEXT).unwrap())
+                            .text(time_text),
+                    )
+                    .into(),
+                Button::new()
+                    .width(Size::px(80.0))
+                    .height(Size::Fill)
+                    .align(Alignment::Center)
+                    .icon(freya_icons::lucide::radio())
+                    .text("LIVE")
+                    .on_press(move |_| {
+                        let mut data = radio.write();
+                        data.playback.time = None;
+                        data.playback.playing = false;
+                    })
+                    .active(playback.time.is_none())
+                    .into(),
+            ])
     }
 }
@@ -6,7 +6,8 @@ use freya::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    components::Map as MapComponent,
+    components::{EventTimers, Map as MapComponent, use_interpolated},
+    utils::{Pose, travel_heading},
     {Data, DataChannel},
 };
 
@@ -36,8 +37,14 @@ impl Component for Minimap {
         let deaths = radio.slice_current(|s| &s.settings.minimap_settings.deaths);
         let monuments = radio.slice_current(|s| &s.settings.minimap_settings.monuments);
         let shops = radio.slice_current(|s| &s.settings.minimap_settings.shops);
+        let zones = radio.slice_current(|s| &s.settings.minimap_settings.zones);
+        let trails = radio.slice_current(|s| &s.settings.minimap_settings.trails);
+        let trail_minutes = radio.slice_current(|s| &s.settings.minimap_settings.trail_minutes);
         let zoom = radio.slice_mut_current(|s| &mut s.settings.minimap_settings.zoom);
         let opacity = radio.slice_current(|s| &s.settings.minimap_settings.opacity);
+        let heading_up = radio.slice_current(|s| &s.settings.minimap_settings.heading_up);
+        let trail_list = radio.slice(DataChannel::TrailsUpdate, |s| &s.trails);
+        let user_data = radio.slice(DataChannel::UserDataUpdate, |s| &s.user_data);
 
         let minimap_size = radio.slice(DataChannel::MinimapSettingsUpdate, |s| &s.settings.minimap_settings.size);
         let monitor_size = radio.slice(DataChannel::MonitorSizeUpdate, |s| &s.monitor_size);
@@ -92,6 +99,28 @@ impl Component for Minimap {
             }
         });
 
+        // The last heading is kept while standing still so the map doesn't swing back north
+        let mut heading = use_state(|| 0.0f32);
+        use_side_effect(move || {
+            let me_steam_id: u64 = user_data
+                .read()
+                .steam_id
+                .clone()
+                .unwrap_or("0".to_string())
+                .parse()
+                .unwrap_or(0);
+            let travel = trail_list
+                .read()
+                .get(&me_steam_id)
+                .and_then(travel_heading)
+                .filter(|travel| *heading.peek() != *travel);
+            if let Some(travel) = travel {
+                *heading.write() = travel;
+            }
+        });
+        let rotation = use_state(|| 0.0f32);
+        let size = minimap_settings.read().size;
+
         rect()
             .width(Size::percent(100.0))
             .height(Size::percent(100.0))
@@ -99,6 +128,11 @@ impl Component for Minimap {
                 rect.corner_radius(1000.0)
             })
             .overflow(Overflow::Clip)
+            .child(HeadingRotation {
+                heading: heading.into(),
+                heading_up: heading_up.clone().into_readable(),
+                rotation: rotation.into(),
+            })
             .maybe_child({
                 Some(
                     MapComponent::new()
@@ -106,12 +140,91 @@ impl Component for Minimap {
                         .center(true)
                         .background_opacity(opacity.into_readable())
                         .zoom(zoom.into_writable())
+                        .rotation(rotation)
                         .grid(grid.into_readable())
                         .markers(markers.into_readable())
                         .deaths(deaths.into_readable())
                         .monuments(monuments.into_readable())
-                        .shops(shops.into_readable()),
+                        .shops(shops.into_readable())
+                        .zones(zones.into_readable())
+                        .trails(trails.into_readable())
+                        .trail_minutes(trail_minutes.into_readable()),
                 )
             })
+            .maybe_child(heading_up.read().then(|| Compass {
+                rotation: rotation.into(),
+                size,
+            }))
+            .maybe_child(minimap_settings.read().timers.then(|| {
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::Fill)
+                    .position(Position::new_absolute())
+                    .layer(Layer::Relative(3))
+                    .padding(Gaps::new(0.0, 0.0, 24.0, 0.0))
+                    .main_align(Alignment::End)
+                    .cross_align(Alignment::Center)
+                    .child(EventTimers::new().compact(true))
+            }))
+    }
+}
+
+/// Glides the map rotation towards the heading. Only this component re-renders on every
+/// frame of a turn, the map and compass just read the rotation.
+#[derive(PartialEq)]
+struct HeadingRotation {
+    heading: Readable<f32>,
+    heading_up: Readable<bool>,
+    rotation: Writable<f32>,
+}
+
+impl Component for HeadingRotation {
+    fn render(&self) -> impl IntoElement {
+        let target = if *self.heading_up.read() {
+            *self.heading.read()
+        } else {
+            0.0
+        };
+        let pose = use_interpolated(Pose::new(0.0, 0.0).rotation(target), false);
+        use_side_effect_with_deps(&pose.rotation, {
+            let mut rotation = self.rotation.clone();
+            move |value| *rotation.write() = *value
+        });
+
+        rect()
+    }
+}
+
+/// "N" badge on the rim of a heading-up minimap, sitting wherever north currently is.
+#[derive(PartialEq)]
+struct Compass {
+    rotation: Readable<f32>,
+    size: f32,
+}
+
+impl Component for Compass {
+    fn render(&self) -> impl IntoElement {
+        let radius = self.size / 2.0 - 14.0;
+        let (sin, cos) = (-*self.rotation.read()).to_radians().sin_cos();
+
+        rect()
+            .position(
+                Position::new_absolute()
+                    .left(self.size / 2.0 + radius * sin - 9.0)
+                    .top(self.size / 2.0 - radius * cos - 9.0),
+            )
+            .layer(Layer::Relative(3))
+            .width(Size::px(18.0))
+            .height(Size::px(18.0))
+            .corner_radius(1000.0)
+            .background(Color::from_hex("#000000b0").unwrap())
+            .center()
+            .child(
+                label()
+                    .font_size(11.0)
+                    .font_weight(FontWeight::BOLD)
+                    .color(Color::from_hex("#f3c86d").unwrap())
+                    .text("N"),
+            )
     }
 }
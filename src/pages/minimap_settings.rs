@@ -1,11 +1,13 @@
+use std::collections::HashMap;
+
 use freya::{prelude::*, radio::use_radio};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Data, DataChannel, colors,
+    ChannelSend, Data, DataChannel, colors,
     components::{
-        DropdownOption, DropdownSettings, MAX_ZOOM, MIN_ZOOM, Setting, SettingType, SliderSettings,
-        ToggleSettings,
+        Button, DropdownOption, DropdownSettings, MAX_ZOOM, MIN_ZOOM, Setting, SettingType,
+        SliderSettings, ToggleSettings,
     },
     pages::Shape,
 };
@@ -20,6 +22,7 @@ pub enum Position {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 #[allow(dead_code)]
 pub struct MinimapSettings {
     pub enabled: bool,
@@ -30,12 +33,18 @@ pub struct MinimapSettings {
     pub offset_y: f32,
     pub opacity: f32,
     pub zoom: f32,
+    /// Turns the map so the local player's direction of travel points up.
+    pub heading_up: bool,
 
     pub grid: bool,
     pub markers: bool,
     pub deaths: bool,
     pub monuments: bool,
     pub shops: bool,
+    pub zones: bool,
+    pub trails: bool,
+    pub trail_minutes: f32,
+    pub timers: bool,
 }
 
 impl Default for MinimapSettings {
@@ -49,12 +58,110 @@ impl Default for MinimapSettings {
             offset_y: 0.0,
             opacity: 100.0,
             zoom: 1.0,
+            heading_up: false,
 
             grid: true,
             markers: true,
             deaths: true,
             monuments: true,
             shops: true,
+            zones: true,
+            trails: false,
+            trail_minutes: 5.0,
+            timers: false,
+        }
+    }
+}
+
+/// Named minimap look that can be switched to from the settings page or the tray.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MinimapPreset {
+    pub name: String,
+    pub settings: MinimapSettings,
+}
+
+impl MinimapPreset {
+    /// The preset's settings, leaving whether the minimap is shown as it is.
+    pub fn apply(&self, current: &MinimapSettings) -> MinimapSettings {
+        MinimapSettings {
+            enabled: current.enabled,
+            ..self.settings.clone()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MinimapPresets {
+    pub presets: Vec<MinimapPreset>,
+    /// Preset name to switch to when connecting, keyed by server id.
+    pub server_defaults: HashMap<String, String>,
+    /// Preset last switched to.
+    pub active: Option<String>,
+}
+
+impl MinimapPresets {
+    pub fn get(&self, name: &str) -> Option<&MinimapPreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    /// Stores `settings` under `name`, replacing a preset of the same name.
+    pub fn save(&mut self, name: String, settings: &MinimapSettings) {
+        let preset = MinimapPreset {
+            name,
+            settings: MinimapSettings {
+                enabled: false,
+                ..settings.clone()
+            },
+        };
+        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+    }
+
+    /// Drops the preset along with every server using it as their default.
+    pub fn remove(&mut self, name: &str) {
+        self.presets.retain(|preset| preset.name != name);
+        self.server_defaults.retain(|_, preset| preset != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+    }
+}
+
+impl Default for MinimapPresets {
+    fn default() -> Self {
+        Self {
+            presets: vec![
+                MinimapPreset {
+                    name: "PvP compact".to_string(),
+                    settings: MinimapSettings {
+                        size: 180.0,
+                        opacity: 80.0,
+                        zoom: 1.4,
+                        heading_up: true,
+                        deaths: false,
+                        monuments: false,
+                        shops: false,
+                        ..Default::default()
+                    },
+                },
+                MinimapPreset {
+                    name: "Farming large".to_string(),
+                    settings: MinimapSettings {
+                        position: Position::BottomRight,
+                        shape: Shape::Square,
+                        size: 400.0,
+                        zoom: 0.8,
+                        trails: true,
+                        timers: true,
+                        ..Default::default()
+                    },
+                },
+            ],
+            server_defaults: HashMap::new(),
+            active: None,
         }
     }
 }
@@ -70,6 +177,55 @@ impl Component for MinimapSettingsPage {
             minimap_settings_state.slice(DataChannel::MonitorSizeUpdate, |s| &s.monitor_size);
 
         let state_tx = minimap_settings_state.read().state_tx.clone().unwrap();
+        let presets_tx = state_tx.clone();
+        let minimap_presets = minimap_settings_state
+            .slice(DataChannel::MinimapPresetsUpdate, |s| {
+                &s.settings.minimap_presets
+            });
+        let selected_server =
+            minimap_settings_state.slice(DataChannel::SelectedServerUpdate, |s| &s.selected_server);
+        let preset_name = use_state(String::new);
+
+        let preset_names: Vec<String> = minimap_presets
+            .read()
+            .presets
+            .iter()
+            .map(|preset| preset.name.clone())
+            .collect();
+        let selected_preset = minimap_presets.read().active.clone();
+        let server_id = selected_server
+            .read()
+            .as_ref()
+            .map(|server| server.id.clone());
+        let server_default = server_id.as_ref().and_then(|server_id| {
+            minimap_presets
+                .read()
+                .server_defaults
+                .get(server_id)
+                .cloned()
+        });
+        let set_server_default = {
+            let presets_tx = presets_tx.clone();
+            let server_id = server_id.clone();
+            let minimap_presets = minimap_presets.clone();
+            move |preset: Option<String>| {
+                let Some(server_id) = server_id.clone() else {
+                    return;
+                };
+                let mut minimap_presets = minimap_presets.read().clone();
+                match preset {
+                    Some(preset) => {
+                        minimap_presets.server_defaults.insert(server_id, preset);
+                    }
+                    None => {
+                        minimap_presets.server_defaults.remove(&server_id);
+                    }
+                }
+                presets_tx
+                    .unbounded_send(ChannelSend::MinimapPresetsUpdate(minimap_presets))
+                    .unwrap();
+            }
+        };
 
         use_hook(|| {
             Platform::get().with_window(None, move |window| {
@@ -140,6 +296,120 @@ impl Component for MinimapSettingsPage {
                             }))
                             .text("ENABLED")
                             .into(),
+                            Setting::new(SettingType::Dropdown(DropdownSettings {
+                                selected: selected_preset.clone().unwrap_or("None".to_string()),
+                                options: preset_names
+                                    .iter()
+                                    .map(|name| DropdownOption {
+                                        name: name.clone(),
+                                        on_select: Some(EventHandler::new({
+                                            let presets_tx = presets_tx.clone();
+                                            let name = name.clone();
+                                            move |_| {
+                                                presets_tx
+                                                    .unbounded_send(
+                                                        ChannelSend::ApplyMinimapPreset(
+                                                            name.clone(),
+                                                        ),
+                                                    )
+                                                    .unwrap();
+                                            }
+                                        })),
+                                        selected: (selected_preset.as_ref() == Some(name)).into(),
+                                    })
+                                    .collect(),
+                            }))
+                            .text("PRESET")
+                            .into(),
+                            // Only offered while connected, the default belongs to that server
+                            match server_id {
+                                Some(_) => Setting::new(SettingType::Dropdown(DropdownSettings {
+                                    selected: server_default.clone().unwrap_or("None".to_string()),
+                                    options: std::iter::once(None)
+                                        .chain(preset_names.iter().cloned().map(Some))
+                                        .map(|preset| DropdownOption {
+                                            name: preset.clone().unwrap_or("None".to_string()),
+                                            on_select: Some(EventHandler::new({
+                                                let set_server_default = set_server_default.clone();
+                                                let preset = preset.clone();
+                                                move |_| set_server_default(preset.clone())
+                                            })),
+                                            selected: (server_default == preset).into(),
+                                        })
+                                        .collect(),
+                                }))
+                                .text("SERVER DEFAULT")
+                                .into(),
+                                None => rect().into(),
+                            },
+                            rect()
+                                .width(Size::Fill)
+                                .padding(8.0)
+                                .spacing(8.0)
+                                .direction(Direction::Horizontal)
+                                .cross_align(Alignment::Center)
+                                .content(Content::Flex)
+                                .children([
+                                    Input::new(preset_name)
+                                        .width(Size::flex(1.0))
+                                        .placeholder("Preset name")
+                                        .into(),
+                                    Button::new()
+                                        .height(Size::px(32.0))
+                                        .padding((4.0, 8.0))
+                                        .corner_radius(4.0)
+                                        .icon(freya_icons::lucide::save())
+                                        .text("SAVE PRESET")
+                                        .on_press({
+                                            let presets_tx = presets_tx.clone();
+                                            let minimap_presets = minimap_presets.clone();
+                                            move |_| {
+                                                let name = preset_name.read().trim().to_string();
+                                                if name.is_empty() {
+                                                    return;
+                                                }
+                                                let mut presets = minimap_presets.read().clone();
+                                                presets.save(
+                                                    name.clone(),
+                                                    &minimap_settings_state
+                                                        .read()
+                                                        .settings
+                                                        .minimap_settings,
+                                                );
+                                                presets.active = Some(name);
+                                                presets_tx
+                                                    .unbounded_send(
+                                                        ChannelSend::MinimapPresetsUpdate(presets),
+                                                    )
+                                                    .unwrap();
+                                            }
+                                        })
+                                        .into(),
+                                    Button::new()
+                                        .height(Size::px(32.0))
+                                        .padding((4.0, 8.0))
+                                        .corner_radius(4.0)
+                                        .icon(freya_icons::lucide::trash_2())
+                                        .text("DELETE")
+                                        .on_press({
+                                            let presets_tx = presets_tx.clone();
+                                            let minimap_presets = minimap_presets.clone();
+                                            move |_| {
+                                                let mut presets = minimap_presets.read().clone();
+                                                let Some(name) = presets.active.clone() else {
+                                                    return;
+                                                };
+                                                presets.remove(&name);
+                                                presets_tx
+                                                    .unbounded_send(
+                                                        ChannelSend::MinimapPresetsUpdate(presets),
+                                                    )
+                                                    .unwrap();
+                                            }
+                                        })
+                                        .into(),
+                                ])
+                                .into(),
                             Setting::new(SettingType::Dropdown(DropdownSettings {
                                 selected: match minimap_settings_state
                                     .read()
@@ -362,6 +632,22 @@ impl Component for MinimapSettingsPage {
                             }))
                             .text("OPACITY")
                             .into(),
+                            Setting::new(SettingType::Toggle(ToggleSettings {
+                                value: minimap_settings_state
+                                    .read()
+                                    .settings
+                                    .minimap_settings
+                                    .heading_up,
+                                on_change: Some(EventHandler::new(move |value: bool| {
+                                    minimap_settings_state
+                                        .write()
+                                        .settings
+                                        .minimap_settings
+                                        .heading_up = value;
+                                })),
+                            }))
+                            .text("HEADING UP")
+                            .into(),
                             rect()
                                 .width(Size::Fill)
                                 .height(Size::px(48.0))
@@ -458,6 +744,73 @@ impl Component for MinimapSettingsPage {
                             }))
                             .text("SHOPS")
                             .into(),
+                            Setting::new(SettingType::Toggle(ToggleSettings {
+                                value: minimap_settings_state
+                                    .read()
+                                    .settings
+                                    .minimap_settings
+                                    .zones,
+                                on_change: Some(EventHandler::new(move |value: bool| {
+                                    minimap_settings_state
+                                        .write()
+                                        .settings
+                                        .minimap_settings
+                                        .zones = value;
+                                })),
+                            }))
+                            .text("ZONES")
+                            .into(),
+                            Setting::new(SettingType::Toggle(ToggleSettings {
+                                value: minimap_settings_state
+                                    .read()
+                                    .settings
+                                    .minimap_settings
+                                    .trails,
+                                on_change: Some(EventHandler::new(move |value: bool| {
+                                    minimap_settings_state
+                                        .write()
+                                        .settings
+                                        .minimap_settings
+                                        .trails = value;
+                                })),
+                            }))
+                            .text("TRAILS")
+                            .into(),
+                            Setting::new(SettingType::Slider(SliderSettings {
+                                value: minimap_settings_state
+                                    .read()
+                                    .settings
+                                    .minimap_settings
+                                    .trail_minutes,
+                                min: 1.0,
+                                max: 60.0,
+                                step: 1.0,
+                                on_change: Some(EventHandler::new(move |value: f32| {
+                                    minimap_settings_state
+                                        .write()
+                                        .settings
+                                        .minimap_settings
+                                        .trail_minutes = value;
+                                })),
+                            }))
+                            .text("TRAIL MINUTES")
+                            .into(),
+                            Setting::new(SettingType::Toggle(ToggleSettings {
+                                value: minimap_settings_state
+                                    .read()
+                                    .settings
+                                    .minimap_settings
+                                    .timers,
+                                on_change: Some(EventHandler::new(move |value: bool| {
+                                    minimap_settings_state
+                                        .write()
+                                        .settings
+                                        .minimap_settings
+                                        .timers = value;
+                                })),
+                            }))
+                            .text("EVENT TIMERS")
+                            .into(),
                         ]),
                     ),
             )
@@ -16,3 +16,11 @@ mod shops;
 pub use shops::*;
 mod team;
 pub use team::*;
+mod timeline;
+pub use timeline::*;
+mod notification_settings;
+pub use notification_settings::*;
+mod geofences;
+pub use geofences::*;
+mod annotations;
+pub use annotations::*;
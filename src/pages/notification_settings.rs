@@ -0,0 +1,260 @@
+use chrono::{Local, Timelike};
+use freya::{prelude::*, radio::use_radio};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Data, DataChannel, colors,
+    components::{Setting, SettingType, SliderSettings, ToggleSettings},
+    utils::save_notification_settings,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NotificationKind {
+    TeammateOnline,
+    TeammateOffline,
+    TeammateDied,
+    TeammateSpawned,
+    Pairing,
+    Team,
+    Alarm,
+    Wipe,
+    Geofence,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub online: bool,
+    pub offline: bool,
+    pub deaths: bool,
+    pub respawns: bool,
+    pub pairing: bool,
+    pub team: bool,
+    pub alarms: bool,
+    pub wipes: bool,
+    pub geofences: bool,
+
+    pub muted_members: Vec<u64>,
+
+    pub quiet_hours: bool,
+    pub quiet_start: u32,
+    pub quiet_end: u32,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            online: true,
+            offline: true,
+            deaths: true,
+            respawns: true,
+            pairing: true,
+            team: true,
+            alarms: true,
+            wipes: true,
+            geofences: true,
+
+            muted_members: Vec::new(),
+
+            quiet_hours: false,
+            quiet_start: 23,
+            quiet_end: 7,
+        }
+    }
+}
+
+impl NotificationSettings {
+    /// Whether a notification of `kind`, optionally about `steam_id`, should be shown right now.
+    pub fn allows(&self, kind: NotificationKind, steam_id: Option<u64>) -> bool {
+        let enabled = match kind {
+            NotificationKind::TeammateOnline => self.online,
+            NotificationKind::TeammateOffline => self.offline,
+            NotificationKind::TeammateDied => self.deaths,
+            NotificationKind::TeammateSpawned => self.respawns,
+            NotificationKind::Pairing => self.pairing,
+            NotificationKind::Team => self.team,
+            NotificationKind::Alarm => self.alarms,
+            NotificationKind::Wipe => self.wipes,
+            NotificationKind::Geofence => self.geofences,
+        };
+        if !enabled {
+            return false;
+        }
+        // Pairing waits on the user, so quiet hours and mutes never hide it
+        if kind == NotificationKind::Pairing {
+            return true;
+        }
+
+        if let Some(steam_id) = steam_id
+            && self.muted_members.contains(&steam_id)
+        {
+            return false;
+        }
+
+        !self.is_quiet_hour(Local::now().hour())
+    }
+
+    fn is_quiet_hour(&self, hour: u32) -> bool {
+        if !self.quiet_hours || self.quiet_start == self.quiet_end {
+            return false;
+        }
+
+        if self.quiet_start < self.quiet_end {
+            hour >= self.quiet_start && hour < self.quiet_end
+        } else {
+            hour >= self.quiet_start || hour < self.quiet_end
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub struct NotificationSettingsPage {}
+
+impl Component for NotificationSettingsPage {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::NotificationSettingsUpdate);
+        let notification_settings = radio.slice_current(|s| &s.settings.notification_settings);
+        let team_members = radio.slice(DataChannel::TeamMembersUpdate, |s| &s.team_info.members);
+
+        use_side_effect(move || {
+            if let Err(err) = save_notification_settings(&notification_settings.read()) {
+                eprintln!("Error saving notification settings: {:?}", err);
+            }
+        });
+
+        let settings = radio.read().settings.notification_settings.clone();
+
+        let toggle = |text: &'static str,
+                      value: bool,
+                      set: fn(&mut NotificationSettings, bool)|
+         -> Element {
+            Setting::new(SettingType::Toggle(ToggleSettings {
+                value,
+                on_change: Some(EventHandler::new(move |value: bool| {
+                    set(&mut radio.write().settings.notification_settings, value);
+                })),
+            }))
+            .text(text)
+            .into()
+        };
+
+        let mut members = team_members.read().values().cloned().collect::<Vec<_>>();
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::Fill)
+            .padding(8.0)
+            .child(
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::Fill)
+                    .background(Color::from_hex(colors::BACKGROUND).unwrap())
+                    .corner_radius(8.0)
+                    .child(
+                        ScrollView::new().width(Size::Fill).height(Size::Fill).child(
+                            rect()
+                                .padding(8.0)
+                                .spacing(4.0)
+                                .child(header(freya_icons::lucide::bell(), "NOTIFICATIONS"))
+                                .children([
+                                    toggle("CAME ONLINE", settings.online, |s, v| s.online = v),
+                                    toggle("WENT OFFLINE", settings.offline, |s, v| {
+                                        s.offline = v
+                                    }),
+                                    toggle("DEATHS", settings.deaths, |s, v| s.deaths = v),
+                                    toggle("RESPAWNS", settings.respawns, |s, v| {
+                                        s.respawns = v
+                                    }),
+                                    toggle("PAIRING REQUESTS", settings.pairing, |s, v| {
+                                        s.pairing = v
+                                    }),
+                                    toggle("TEAM MESSAGES", settings.team, |s, v| s.team = v),
+                                    toggle("ALARMS", settings.alarms, |s, v| s.alarms = v),
+                                    toggle("SERVER WIPES", settings.wipes, |s, v| s.wipes = v),
+                                    toggle("GEOFENCES", settings.geofences, |s, v| {
+                                        s.geofences = v
+                                    }),
+                                ])
+                                .child(header(freya_icons::lucide::moon(), "QUIET HOURS"))
+                                .children([
+                                    toggle("ENABLED", settings.quiet_hours, |s, v| {
+                                        s.quiet_hours = v
+                                    }),
+                                    Setting::new(SettingType::Slider(SliderSettings {
+                                        value: settings.quiet_start as f32,
+                                        min: 0.0,
+                                        max: 23.0,
+                                        step: 1.0,
+                                        on_change: Some(EventHandler::new(move |value: f32| {
+                                            radio
+                                                .write()
+                                                .settings
+                                                .notification_settings
+                                                .quiet_start = value as u32;
+                                        })),
+                                    }))
+                                    .text(format!("FROM {:02}:00", settings.quiet_start))
+                                    .into(),
+                                    Setting::new(SettingType::Slider(SliderSettings {
+                                        value: settings.quiet_end as f32,
+                                        min: 0.0,
+                                        max: 23.0,
+                                        step: 1.0,
+                                        on_change: Some(EventHandler::new(move |value: f32| {
+                                            radio
+                                                .write()
+                                                .settings
+                                                .notification_settings
+                                                .quiet_end = value as u32;
+                                        })),
+                                    }))
+                                    .text(format!("UNTIL {:02}:00", settings.quiet_end))
+                                    .into(),
+                                ])
+                                .child(header(freya_icons::lucide::users_round(), "TEAMMATES"))
+                                .children(members.into_iter().map(|member| {
+                                    let steam_id = member.steam_id;
+                                    Setting::new(SettingType::Toggle(ToggleSettings {
+                                        value: !settings.muted_members.contains(&steam_id),
+                                        on_change: Some(EventHandler::new(move |value: bool| {
+                                            let mut data = radio.write();
+                                            let muted_members =
+                                                &mut data.settings.notification_settings.muted_members;
+                                            muted_members.retain(|id| *id != steam_id);
+                                            if !value {
+                                                muted_members.push(steam_id);
+                                            }
+                                        })),
+                                    }))
+                                    .text(member.name.to_uppercase())
+                                    .into()
+                                })),
+                        ),
+                    ),
+            )
+    }
+}
+
+fn header(icon: Bytes, text: &'static str) -> Element {
+    rect()
+        .width(Size::Fill)
+        .height(Size::px(48.0))
+        .padding(8.0)
+        .spacing(8.0)
+        .direction(Direction::Horizontal)
+        .cross_align(Alignment::Center)
+        .children([
+            svg(icon)
+                .height(Size::Fill)
+                .color(Color::from_hex(colors::SELECT).unwrap())
+                .into(),
+            label()
+                .font_size(24.0)
+                .font_weight(FontWeight::BOLD)
+                .color(Color::from_hex(colors::TEXT).unwrap())
+                .text(text)
+                .into(),
+        ])
+        .into()
+}
@@ -1,12 +1,20 @@
 use freya::{prelude::*, radio::use_radio};
 use rustplus_rs::AppMarkerType;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::LazyLock};
 
-use crate::{Data, DataChannel, colors, components::CachedImage};
+use crate::{Data, DataChannel, colors, components::CachedImage, utils::grid_reference};
 
 static ITEM_DATA: &'static [u8] = include_bytes!("./../assets/item_data.json");
 
+static ITEMS: LazyLock<HashMap<i32, Item>> = LazyLock::new(|| {
+    serde_json::from_slice::<Vec<Item>>(ITEM_DATA)
+        .expect("Item data should be loaded")
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect()
+});
+
 #[derive(PartialEq)]
 enum OrderPartType {
     Selling,
@@ -95,13 +103,17 @@ struct Item {
 pub struct Shops {}
 impl Component for Shops {
     fn render(&self) -> impl IntoElement {
-        let map_markers_binding = use_radio::<Data, DataChannel>(DataChannel::MapMarkersUpdate);
-        let map_markers = map_markers_binding.read().map_markers.clone();
-
-        let items: Vec<Item> =
-            serde_json::from_slice::<Vec<Item>>(ITEM_DATA).expect("Item data should be loaded");
-
-        let map: HashMap<i32, Item> = items.into_iter().map(|item| (item.id, item)).collect();
+        // Only listens for shops opening and closing, each card follows its own marker
+        let radio = use_radio::<Data, DataChannel>(DataChannel::MapMarkersUpdate);
+        let mut shop_ids: Vec<u32> = radio
+            .read()
+            .map_markers
+            .markers
+            .values()
+            .filter(|marker| marker.r#type() == AppMarkerType::VendingMachine)
+            .map(|marker| marker.id)
+            .collect();
+        shop_ids.sort();
 
         rect()
             .width(Size::Fill)
@@ -119,104 +131,115 @@ impl Component for Shops {
                     .width(Size::Fill)
                     .height(Size::Fill)
                     .spacing(8.0)
-                    .children(map_markers.markers.iter().filter_map(|marker| {
-                        if marker.r#type() != AppMarkerType::VendingMachine {
-                            println!("Marker type: {:?}", marker.r#type());
+                    .children(
+                        shop_ids
+                            .into_iter()
+                            .map(|marker_id| ShopCard { marker_id }.into()),
+                    )
+                    .into(),
+            ])
+    }
+}
+
+#[derive(PartialEq)]
+struct ShopCard {
+    marker_id: u32,
+}
+
+impl Component for ShopCard {
+    fn render(&self) -> impl IntoElement {
+        let radio = use_radio::<Data, DataChannel>(DataChannel::MarkerUpdate(self.marker_id));
+        let Some(marker) = radio
+            .read()
+            .map_markers
+            .markers
+            .get(&self.marker_id)
+            .cloned()
+        else {
+            return rect().into();
+        };
+        let map_size = radio.read().info_state.map_size;
+        let map = &*ITEMS;
+
+        rect()
+            .width(Size::Fill)
+            .background(Color::from_hex("#222222").unwrap())
+            .corner_radius(8.0)
+            .children([
+                rect()
+                    .width(Size::Fill)
+                    .padding(Gaps::from((4.0, 8.0)))
+                    .direction(Direction::Horizontal)
+                    .main_align(Alignment::SpaceBetween)
+                    .cross_align(Alignment::Center)
+                    .color(Color::from_hex(colors::TEXT).unwrap())
+                    .font_size(15.0)
+                    .font_weight(FontWeight::BOLD)
+                    .children([
+                        label().text(marker.name.clone()).into(),
+                        label()
+                            .text(map_size.map_or(String::new(), |size| {
+                                grid_reference(marker.x, marker.y, size as f32)
+                            }))
+                            .into(),
+                    ])
+                    .into(),
+                rect()
+                    .width(Size::percent(50.0))
+                    .padding(8.0)
+                    .spacing(8.0)
+                    .children(marker.sell_orders.iter().filter_map(|sell_order| {
+                        let selling_item = map.get(&sell_order.item_id);
+
+                        let buying_item = map.get(&sell_order.currency_id);
+
+                        if selling_item.is_none() || buying_item.is_none() {
                             return None;
                         }
+
+                        let selling_item = selling_item.unwrap();
+                        let buying_item = buying_item.unwrap();
+
                         Some(
                             rect()
                                 .width(Size::Fill)
-                                .background(Color::from_hex("#222222").unwrap())
-                                .corner_radius(8.0)
+                                .height(Size::px(56.0))
+                                .background(Color::from_hex("#5D5D5D").unwrap())
+                                .corner_radius(CornerRadius::new_all(8.0))
+                                .direction(Direction::Horizontal)
+                                .padding(4.0)
+                                .spacing(4.0)
+                                .content(Content::Flex)
                                 .children([
+                                    OrderPart::new(
+                                        selling_item.icon_url.clone(),
+                                        OrderPartType::Selling,
+                                        sell_order.quantity,
+                                    )
+                                    .width(Size::flex(1.0))
+                                    .into(),
+                                    OrderPart::new(
+                                        buying_item.icon_url.clone(),
+                                        OrderPartType::Price,
+                                        sell_order.cost_per_item,
+                                    )
+                                    .width(Size::flex(1.0))
+                                    .into(),
                                     rect()
-                                        .width(Size::Fill)
-                                        .padding(Gaps::from((4.0, 8.0)))
-                                        .direction(Direction::Horizontal)
-                                        .main_align(Alignment::SpaceBetween)
+                                        .width(Size::flex(1.0))
+                                        .height(Size::Fill)
+                                        .main_align(Alignment::Center)
                                         .cross_align(Alignment::Center)
-                                        .color(Color::from_hex(colors::TEXT).unwrap())
-                                        .font_size(15.0)
-                                        .font_weight(FontWeight::BOLD)
-                                        .children([
-                                            label().text(marker.name.clone()).into(),
-                                            label().text("G20").into(),
-                                        ])
-                                        .into(),
-                                    rect()
-                                        .width(Size::percent(50.0))
-                                        .padding(8.0)
-                                        .spacing(8.0)
-                                        .children(marker.sell_orders.iter().filter_map(
-                                            |sell_order| {
-                                                let selling_item = map.get(&sell_order.item_id);
-
-                                                let buying_item = map.get(&sell_order.currency_id);
-
-                                                if selling_item.is_none() || buying_item.is_none() {
-                                                    return None;
-                                                }
-
-                                                let selling_item = selling_item.unwrap();
-                                                let buying_item = buying_item.unwrap();
-
-                                                Some(
-                                                    rect()
-                                                        .width(Size::Fill)
-                                                        .height(Size::px(56.0))
-                                                        .background(
-                                                            Color::from_hex("#5D5D5D").unwrap(),
-                                                        )
-                                                        .corner_radius(CornerRadius::new_all(8.0))
-                                                        .direction(Direction::Horizontal)
-                                                        .padding(4.0)
-                                                        .spacing(4.0)
-                                                        .content(Content::Flex)
-                                                        .children([
-                                                            OrderPart::new(
-                                                                selling_item.icon_url.clone(),
-                                                                OrderPartType::Selling,
-                                                                sell_order.quantity,
-                                                            )
-                                                            .width(Size::flex(1.0))
-                                                            .into(),
-                                                            OrderPart::new(
-                                                                buying_item.icon_url.clone(),
-                                                                OrderPartType::Price,
-                                                                sell_order.cost_per_item,
-                                                            )
-                                                            .width(Size::flex(1.0))
-                                                            .into(),
-                                                            rect()
-                                                                .width(Size::flex(1.0))
-                                                                .height(Size::Fill)
-                                                                .main_align(Alignment::Center)
-                                                                .cross_align(Alignment::Center)
-                                                                .child(
-                                                                    label()
-                                                                        .color(
-                                                                            Color::from_hex(
-                                                                                "#FFFFFF",
-                                                                            )
-                                                                            .unwrap(),
-                                                                        )
-                                                                        .font_size(14.0)
-                                                                        .font_weight(
-                                                                            FontWeight::EXTRA_BOLD,
-                                                                        )
-                                                                        .text(format!(
-                                                                            "{} IN STOCK",
-                                                                            sell_order
-                                                                                .amount_in_stock
-                                                                        )),
-                                                                )
-                                                                .into(),
-                                                        ])
-                                                        .into(),
-                                                )
-                                            },
-                                        ))
+                                        .child(
+                                            label()
+                                                .color(Color::from_hex("#FFFFFF").unwrap())
+                                                .font_size(14.0)
+                                                .font_weight(FontWeight::EXTRA_BOLD)
+                                                .text(format!(
+                                                    "{} IN STOCK",
+                                                    sell_order.amount_in_stock
+                                                )),
+                                        )
                                         .into(),
                                 ])
                                 .into(),
@@ -0,0 +1,162 @@
+use chrono::prelude::*;
+use euclid::Point2D;
+use freya::{prelude::*, radio::use_radio};
+use freya_router::prelude::RouterContext;
+use timeago::Formatter;
+
+use crate::{
+    Data, DataChannel,
+    app::Route,
+    colors,
+    components::Button,
+    utils::{TimelineCategory, TimelineEvent, TimelineEventKind},
+};
+
+#[derive(PartialEq)]
+pub struct Timeline {}
+impl Component for Timeline {
+    fn render(&self) -> impl IntoElement {
+        let radio = use_radio::<Data, DataChannel>(DataChannel::TimelineUpdate);
+        let timeline = radio.slice_current(|s| &s.timeline);
+
+        let mut filter: State<Option<TimelineCategory>> = use_state(|| None);
+
+        let filters = [
+            ("ALL", None),
+            ("TEAM", Some(TimelineCategory::Team)),
+            ("EVENTS", Some(TimelineCategory::Events)),
+            ("ALARMS", Some(TimelineCategory::Alarms)),
+            ("CHAT", Some(TimelineCategory::Chat)),
+        ];
+
+        let events = timeline
+            .read()
+            .iter()
+            .rev()
+            .filter(|event| match filter() {
+                Some(category) => event.kind.category() == category,
+                None => true,
+            })
+            .cloned()
+            .collect::<Vec<TimelineEvent>>();
+
+        rect()
+            .width(Size::Fill)
+            .height(Size::Fill)
+            .padding(8.0)
+            .spacing(8.0)
+            .children([
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::px(40.0))
+                    .spacing(4.0)
+                    .direction(Direction::Horizontal)
+                    .children(
+                        filters
+                            .into_iter()
+                            .map(|(name, category)| {
+                                Button::new()
+                                    .width(Size::px(90.0))
+                                    .height(Size::Fill)
+                                    .align(Alignment::Center)
+                                    .text(name)
+                                    .on_press(move |_| {
+                                        *filter.write() = category;
+                                    })
+                                    .active(filter() == category)
+                                    .into()
+                            })
+                            .collect::<Vec<Element>>(),
+                    )
+                    .into(),
+                rect()
+                    .width(Size::Fill)
+                    .height(Size::Fill)
+                    .background(Color::from_hex(colors::BACKGROUND).unwrap())
+                    .corner_radius(8.0)
+                    .child(if events.is_empty() {
+                        rect()
+                            .expanded()
+                            .center()
+                            .child(
+                                label()
+                                    .color(Color::from_hex(colors::TEXT).unwrap())
+                                    .text("No events yet"),
+                            )
+                            .into_element()
+                    } else {
+                        ScrollView::new()
+                            .width(Size::Fill)
+                            .height(Size::Fill)
+                            .child(
+                                rect().padding(8.0).spacing(4.0).children(
+                                    events
+                                        .into_iter()
+                                        .map(|event| TimelineEntry { event }.into())
+                                        .collect::<Vec<Element>>(),
+                                ),
+                            )
+                            .into_element()
+                    })
+                    .into(),
+            ])
+    }
+}
+
+#[derive(PartialEq)]
+struct TimelineEntry {
+    event: TimelineEvent,
+}
+
+impl Component for TimelineEntry {
+    fn render(&self) -> impl IntoElement {
+        let mut radio = use_radio::<Data, DataChannel>(DataChannel::NoUpdate);
+
+        let time = match DateTime::from_timestamp(self.event.timestamp, 0) {
+            Some(timestamp) => Formatter::new().convert_chrono(timestamp, Utc::now()),
+            None => "Unknown".to_string(),
+        };
+
+        let description = match &self.event.kind {
+            TimelineEventKind::Alarm { message, .. }
+            | TimelineEventKind::ChatMention { message, .. } => message.clone(),
+            _ => self.event.location.clone().unwrap_or_default(),
+        };
+
+        Button::new()
+            .width(Size::Fill)
+            .height(Size::px(48.0))
+            .padding(8.0)
+            .corner_radius(8.0)
+            .background(Color::from_hex(colors::BACKGROUND_DARK).unwrap())
+            .on_press({
+                let position = self.event.x.zip(self.event.y);
+                move |_| {
+                    if let Some((x, y)) = position {
+                        radio
+                            .write_channel(DataChannel::MapSettingsUpdate)
+                            .settings
+                            .map_settings
+                            .center = false;
+                        radio.write_channel(DataChannel::MapFocusUpdate).map_focus =
+                            Some(Point2D::new(x, y));
+                        RouterContext::get().replace(Route::Map);
+                    }
+                }
+            })
+            .text(format!(
+                "{}  {}{}  {}",
+                self.event
+                    .grid
+                    .clone()
+                    .unwrap_or_else(|| "--".to_string()),
+                self.event.kind.title(),
+                if description.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {}", description)
+                },
+                time
+            ))
+    }
+}
@@ -0,0 +1,269 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::APP_DIR_NAME;
+
+pub const ANNOTATIONS_DIR_NAME: &str = "annotations";
+
+/// Version written into exported annotation sets, bumped whenever the format changes.
+pub const ANNOTATION_EXPORT_VERSION: u32 = 1;
+
+/// Number of entries `index_to_color` and `index_to_icon` know about.
+pub const ANNOTATION_COLORS: i32 = 6;
+pub const ANNOTATION_ICONS: i32 = 12;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AnnotationTool {
+    Pin,
+    Line,
+    Polygon,
+}
+
+impl AnnotationTool {
+    /// Points needed before the drawing can be finished.
+    pub fn min_points(&self) -> usize {
+        match self {
+            AnnotationTool::Pin => 1,
+            AnnotationTool::Line => 2,
+            AnnotationTool::Polygon => 3,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            AnnotationTool::Pin => "PIN",
+            AnnotationTool::Line => "LINE",
+            AnnotationTool::Polygon => "POLYGON",
+        }
+    }
+}
+
+/// Annotation geometry in world coordinates.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum AnnotationShape {
+    Pin { x: f32, y: f32 },
+    Line { points: Vec<(f32, f32)> },
+    Polygon { points: Vec<(f32, f32)> },
+}
+
+impl AnnotationShape {
+    pub fn from_points(tool: AnnotationTool, points: Vec<(f32, f32)>) -> Option<Self> {
+        if points.len() < tool.min_points() {
+            return None;
+        }
+        Some(match tool {
+            AnnotationTool::Pin => AnnotationShape::Pin {
+                x: points[0].0,
+                y: points[0].1,
+            },
+            AnnotationTool::Line => AnnotationShape::Line { points },
+            AnnotationTool::Polygon => AnnotationShape::Polygon { points },
+        })
+    }
+
+    pub fn tool(&self) -> AnnotationTool {
+        match self {
+            AnnotationShape::Pin { .. } => AnnotationTool::Pin,
+            AnnotationShape::Line { .. } => AnnotationTool::Line,
+            AnnotationShape::Polygon { .. } => AnnotationTool::Polygon,
+        }
+    }
+
+    /// Where the label goes, the pin itself, the start of a line or the middle of a polygon.
+    pub fn anchor(&self) -> (f32, f32) {
+        match self {
+            AnnotationShape::Pin { x, y } => (*x, *y),
+            AnnotationShape::Line { points } => points.first().copied().unwrap_or_default(),
+            AnnotationShape::Polygon { points } => {
+                let count = points.len().max(1) as f32;
+                let (x, y) = points
+                    .iter()
+                    .fold((0.0, 0.0), |(x, y), point| (x + point.0, y + point.1));
+                (x / count, y / count)
+            }
+        }
+    }
+
+    /// Straight pieces to draw, polygons are closed back to their first point.
+    pub fn segments(&self) -> Vec<((f32, f32), (f32, f32))> {
+        match self {
+            AnnotationShape::Pin { .. } => Vec::new(),
+            AnnotationShape::Line { points } => {
+                points.windows(2).map(|pair| (pair[0], pair[1])).collect()
+            }
+            AnnotationShape::Polygon { points } => (0..points.len())
+                .map(|i| (points[i], points[(i + 1) % points.len()]))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Annotation {
+    pub id: u64,
+    pub label: String,
+    #[serde(default)]
+    pub note: String,
+    /// Index for `index_to_icon`, only shown on pins.
+    pub icon: i32,
+    /// Index for `index_to_color`.
+    pub color: i32,
+    pub shape: AnnotationShape,
+}
+
+/// An annotation being placed on the map, in world coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnnotationDraft {
+    pub tool: AnnotationTool,
+    pub points: Vec<(f32, f32)>,
+}
+
+impl AnnotationDraft {
+    pub fn new(tool: AnnotationTool) -> Self {
+        Self {
+            tool,
+            points: Vec::new(),
+        }
+    }
+
+    pub fn can_finish(&self) -> bool {
+        self.points.len() >= self.tool.min_points()
+    }
+}
+
+/// Shareable annotation set. Coordinates are world units, so a set stays valid
+/// no matter how large the map image of the importing client is.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AnnotationExport {
+    pub version: u32,
+    pub exported_at: i64,
+    pub server_name: Option<String>,
+    pub map_size: Option<u32>,
+    pub annotations: Vec<Annotation>,
+}
+
+impl AnnotationExport {
+    pub fn new(
+        annotations: Vec<Annotation>,
+        server_name: Option<String>,
+        map_size: Option<u32>,
+    ) -> Self {
+        Self {
+            version: ANNOTATION_EXPORT_VERSION,
+            exported_at: chrono::Utc::now().timestamp(),
+            server_name,
+            map_size,
+            annotations,
+        }
+    }
+}
+
+/// Adds imported annotations to the current set, imported ones win when ids collide
+/// so importing an updated set from a teammate refreshes the older copy.
+pub fn merge_annotations(annotations: &mut Vec<Annotation>, imported: Vec<Annotation>) {
+    for annotation in imported {
+        match annotations.iter_mut().find(|a| a.id == annotation.id) {
+            Some(existing) => *existing = annotation,
+            None => annotations.push(annotation),
+        }
+    }
+}
+
+pub fn export_annotations(
+    path: &Path,
+    export: &AnnotationExport,
+) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(path, serde_json::to_string_pretty(export)?)?;
+
+    Ok(())
+}
+
+/// Rejects sets made for a map of a different size than `map_size`, their world
+/// coordinates would land in the wrong places.
+pub fn import_annotations(
+    path: &Path,
+    map_size: Option<u32>,
+) -> Result<AnnotationExport, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path)?;
+    let export = serde_json::from_str::<AnnotationExport>(&content)?;
+
+    if export.version > ANNOTATION_EXPORT_VERSION {
+        return Err(format!(
+            "Annotation set version {} is newer than supported version {}",
+            export.version, ANNOTATION_EXPORT_VERSION
+        )
+        .into());
+    }
+
+    if let (Some(map_size), Some(export_map_size)) = (map_size, export.map_size)
+        && map_size != export_map_size
+    {
+        return Err(format!(
+            "Annotation set is for a map of size {}, the current map is {}",
+            export_map_size, map_size
+        )
+        .into());
+    }
+
+    Ok(export)
+}
+
+fn annotations_filename(server_id: &str, wipe_time: u32) -> String {
+    format!("{}_{}.json", server_id, wipe_time)
+}
+
+pub fn load_annotations(
+    server_id: &str,
+    wipe_time: u32,
+) -> Result<Vec<Annotation>, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let annotations_dir = config_dir.join(APP_DIR_NAME).join(ANNOTATIONS_DIR_NAME);
+    let filename = annotations_filename(server_id, wipe_time);
+
+    if !annotations_dir.exists() {
+        std::fs::create_dir_all(&annotations_dir)?;
+    }
+
+    let data = match std::fs::read_to_string(annotations_dir.join(&filename)) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_annotations] No {filename} found...");
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }?;
+
+    let annotations = match data {
+        Some(content) => match serde_json::from_str::<Vec<Annotation>>(&content) {
+            Ok(annotations) => annotations,
+            Err(e) => {
+                println!("[load_annotations] Failed to parse {filename}: {:?}", e);
+                vec![]
+            }
+        },
+        None => vec![],
+    };
+
+    Ok(annotations)
+}
+
+pub fn save_annotations(
+    server_id: &str,
+    wipe_time: u32,
+    annotations: &Vec<Annotation>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let annotations_dir = config_dir.join(APP_DIR_NAME).join(ANNOTATIONS_DIR_NAME);
+
+    if !annotations_dir.exists() {
+        std::fs::create_dir_all(&annotations_dir)?;
+    }
+
+    std::fs::write(
+        annotations_dir.join(annotations_filename(server_id, wipe_time)),
+        serde_json::to_string_pretty(annotations)?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,299 @@
+use std::fmt;
+
+/// Side of one in-game grid cell in world units, the game fits as many as it can into the map.
+pub const GRID_CELL_SIZE: f32 = 146.25;
+
+pub fn number_to_letters(n: u32) -> String {
+    let mut result = String::new();
+    let mut num = n + 1;
+
+    while num > 0 {
+        num -= 1;
+        let remainder = (num % 26) as u8;
+        result.insert(0, (b'A' + remainder) as char);
+        num /= 26;
+    }
+
+    result
+}
+
+/// Inverse of [`number_to_letters`], "A" is 0 and "AA" is 26.
+pub fn letters_to_number(letters: &str) -> Option<u32> {
+    if letters.is_empty() {
+        return None;
+    }
+
+    letters
+        .chars()
+        .try_fold(0u32, |n, c| {
+            let c = c.to_ascii_uppercase();
+            c.is_ascii_uppercase()
+                .then(|| n * 26 + (c as u32 - 'A' as u32 + 1))
+        })
+        .map(|n| n - 1)
+}
+
+/// Cell of the in-game grid plus where inside it a position is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridPosition {
+    pub column: u32,
+    pub row: u32,
+    /// Position inside the cell from its top left corner, both in `0.0..1.0`.
+    pub offset: (f32, f32),
+}
+
+impl GridPosition {
+    /// Keypad style sub-cell, 1 is the top left third of the cell and 9 the bottom right.
+    pub fn sub_cell(&self) -> u8 {
+        let third = |offset: f32| ((offset * 3.0).floor() as u8).min(2);
+        third(self.offset.1) * 3 + third(self.offset.0) + 1
+    }
+
+    /// Reference with the sub-cell, e.g. "G12-3".
+    pub fn precise(&self) -> String {
+        format!("{}-{}", self, self.sub_cell())
+    }
+
+    /// Parses references like "G12" or "g12-3", the sub-cell picks a third of the cell.
+    pub fn parse(reference: &str) -> Option<Self> {
+        let reference = reference.trim();
+        let (cell, sub_cell) = match reference.split_once('-') {
+            Some((cell, sub_cell)) => (cell, Some(sub_cell.parse::<u8>().ok()?)),
+            None => (reference, None),
+        };
+
+        let split = cell.find(|c: char| c.is_ascii_digit())?;
+        let column = letters_to_number(&cell[..split])?;
+        let row = cell[split..].parse().ok()?;
+
+        let offset = match sub_cell {
+            Some(sub_cell @ 1..=9) => {
+                let index = (sub_cell - 1) as f32;
+                (
+                    ((index % 3.0) + 0.5) / 3.0,
+                    ((index / 3.0).floor() + 0.5) / 3.0,
+                )
+            }
+            Some(_) => return None,
+            None => (0.5, 0.5),
+        };
+
+        Some(Self {
+            column,
+            row,
+            offset,
+        })
+    }
+}
+
+impl fmt::Display for GridPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", number_to_letters(self.column), self.row)
+    }
+}
+
+/// Converts between world positions, positions on the drawn map and grid references.
+///
+/// World positions grow right and up from the bottom left of the playable area, while the
+/// drawn map grows right and down and has an ocean margin around the playable area.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapGeometry {
+    /// Playable area in world units, `InfoState.map_size`.
+    pub world_size: f32,
+    pub margin: f32,
+}
+
+impl MapGeometry {
+    pub fn new(world_size: f32, margin: f32) -> Self {
+        Self { world_size, margin }
+    }
+
+    /// Size of the drawn map including the margin on both sides.
+    pub fn content_size(&self) -> f32 {
+        self.world_size + self.margin * 2.0
+    }
+
+    pub fn cells(&self) -> u32 {
+        ((self.world_size / GRID_CELL_SIZE).floor() as u32).max(1)
+    }
+
+    /// Actual cell size, slightly larger than [`GRID_CELL_SIZE`] so the cells fill the map.
+    pub fn cell_size(&self) -> f32 {
+        self.world_size / self.cells() as f32
+    }
+
+    pub fn world_to_content(&self, x: f32, y: f32) -> (f32, f32) {
+        (x + self.margin, self.world_size + self.margin - y)
+    }
+
+    pub fn content_to_world(&self, left: f32, top: f32) -> (f32, f32) {
+        (left - self.margin, self.world_size + self.margin - top)
+    }
+
+    /// Grid cell of a world position, positions off the grid clamp to the closest cell.
+    pub fn grid_position(&self, x: f32, y: f32) -> GridPosition {
+        let cells = self.cells();
+        let cell_size = self.cell_size();
+
+        let from_left = x / cell_size;
+        let from_top = (self.world_size - y) / cell_size;
+        let column = (from_left.floor().max(0.0) as u32).min(cells - 1);
+        let row = (from_top.floor().max(0.0) as u32).min(cells - 1);
+
+        GridPosition {
+            column,
+            row,
+            offset: (
+                (from_left - column as f32).clamp(0.0, 1.0),
+                (from_top - row as f32).clamp(0.0, 1.0),
+            ),
+        }
+    }
+
+    /// World position of a point inside a grid cell.
+    pub fn grid_to_world(&self, position: &GridPosition) -> (f32, f32) {
+        let cell_size = self.cell_size();
+        (
+            (position.column as f32 + position.offset.0) * cell_size,
+            self.world_size - (position.row as f32 + position.offset.1) * cell_size,
+        )
+    }
+}
+
+/// Converts a world position into the in-game grid reference, e.g. "G12".
+pub fn grid_reference(x: f32, y: f32, map_size: f32) -> String {
+    MapGeometry::new(map_size, 0.0)
+        .grid_position(x, y)
+        .to_string()
+}
+
+/// Grid reference with the keypad sub-cell, e.g. "G12-3".
+pub fn precise_grid_reference(x: f32, y: f32, map_size: f32) -> String {
+    MapGeometry::new(map_size, 0.0)
+        .grid_position(x, y)
+        .precise()
+}
+
+/// World space rectangle of the map that is currently on screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VisibleBounds {
+    pub min_x: f32,
+    pub max_x: f32,
+    pub min_y: f32,
+    pub max_y: f32,
+}
+
+impl VisibleBounds {
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_y..=self.max_y).contains(&y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP_SIZE: f32 = 4500.0;
+
+    fn assert_close(a: (f32, f32), b: (f32, f32)) {
+        assert!(
+            (a.0 - b.0).abs() < 0.01 && (a.1 - b.1).abs() < 0.01,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn content_round_trip() {
+        let geometry = MapGeometry::new(MAP_SIZE, 500.0);
+        for point in [
+            (0.0, 0.0),
+            (MAP_SIZE, MAP_SIZE),
+            (0.0, MAP_SIZE),
+            (MAP_SIZE, 0.0),
+            (1234.5, 3210.0),
+            (-300.0, MAP_SIZE + 250.0),
+        ] {
+            let (left, top) = geometry.world_to_content(point.0, point.1);
+            assert_close(geometry.content_to_world(left, top), point);
+        }
+    }
+
+    #[test]
+    fn content_corners() {
+        let geometry = MapGeometry::new(MAP_SIZE, 500.0);
+        assert_close(geometry.world_to_content(0.0, MAP_SIZE), (500.0, 500.0));
+        assert_close(
+            geometry.world_to_content(MAP_SIZE, 0.0),
+            (MAP_SIZE + 500.0, MAP_SIZE + 500.0),
+        );
+    }
+
+    #[test]
+    fn letters_round_trip() {
+        for n in [0, 1, 25, 26, 27, 51, 52, 701, 702] {
+            assert_eq!(letters_to_number(&number_to_letters(n)), Some(n));
+        }
+        assert_eq!(letters_to_number(""), None);
+        assert_eq!(letters_to_number("A1"), None);
+    }
+
+    #[test]
+    fn grid_reference_round_trip() {
+        let geometry = MapGeometry::new(MAP_SIZE, 0.0);
+        let cells = geometry.cells();
+        for point in [
+            (10.0, MAP_SIZE - 10.0),
+            (MAP_SIZE - 10.0, MAP_SIZE - 10.0),
+            (MAP_SIZE - 10.0, 10.0),
+            (2250.0, 2250.0),
+        ] {
+            let expected = geometry.grid_position(point.0, point.1);
+            let parsed = GridPosition::parse(&grid_reference(point.0, point.1, MAP_SIZE)).unwrap();
+            assert_eq!((parsed.column, parsed.row), (expected.column, expected.row));
+        }
+
+        // Top left is A0, the last column and row are on the opposite corner
+        assert_eq!(grid_reference(0.0, MAP_SIZE, MAP_SIZE), "A0");
+        let last = GridPosition::parse(&grid_reference(MAP_SIZE, 0.0, MAP_SIZE)).unwrap();
+        assert_eq!((last.column, last.row), (cells - 1, cells - 1));
+    }
+
+    #[test]
+    fn outside_positions_clamp() {
+        let cells = MapGeometry::new(MAP_SIZE, 0.0).cells();
+        let below = GridPosition::parse(&grid_reference(-200.0, -200.0, MAP_SIZE)).unwrap();
+        assert_eq!((below.column, below.row), (0, cells - 1));
+        let above = GridPosition::parse(&grid_reference(
+            MAP_SIZE + 200.0,
+            MAP_SIZE + 200.0,
+            MAP_SIZE,
+        ))
+        .unwrap();
+        assert_eq!((above.column, above.row), (cells - 1, 0));
+    }
+
+    #[test]
+    fn precise_round_trip() {
+        let geometry = MapGeometry::new(MAP_SIZE, 0.0);
+        for point in [
+            (20.0, MAP_SIZE - 20.0),
+            (1000.0, 1000.0),
+            (MAP_SIZE - 5.0, 5.0),
+        ] {
+            let reference = precise_grid_reference(point.0, point.1, MAP_SIZE);
+            let parsed = GridPosition::parse(&reference).unwrap();
+            let (x, y) = geometry.grid_to_world(&parsed);
+            assert_eq!(precise_grid_reference(x, y, MAP_SIZE), reference);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid() {
+        assert_eq!(GridPosition::parse("12"), None);
+        assert_eq!(GridPosition::parse("G"), None);
+        assert_eq!(GridPosition::parse("G12-0"), None);
+        assert_eq!(GridPosition::parse("G12-x"), None);
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use rustplus_rs::AppMarkerType;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::APP_DIR_NAME;
+
+pub const SPAWNS_DIR_NAME: &str = "spawns";
+
+/// Spawns kept per event, older ones stop influencing the estimate.
+const MAX_SPAWNS: usize = 50;
+/// Longer gaps most likely mean we were not connected, so they are not real intervals.
+const MAX_INTERVAL_SECS: i64 = 4 * 60 * 60;
+/// Intervals needed before an estimate is shown.
+const MIN_INTERVALS: usize = 2;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpawnEvent {
+    CargoShip,
+    PatrolHelicopter,
+    Chinook,
+}
+
+impl SpawnEvent {
+    pub const ALL: [SpawnEvent; 3] = [
+        SpawnEvent::CargoShip,
+        SpawnEvent::PatrolHelicopter,
+        SpawnEvent::Chinook,
+    ];
+
+    pub fn from_marker_type(marker_type: AppMarkerType) -> Option<Self> {
+        match marker_type {
+            AppMarkerType::CargoShip => Some(SpawnEvent::CargoShip),
+            AppMarkerType::PatrolHelicopter => Some(SpawnEvent::PatrolHelicopter),
+            AppMarkerType::Ch47 => Some(SpawnEvent::Chinook),
+            _ => None,
+        }
+    }
+
+    pub fn marker_type(&self) -> AppMarkerType {
+        match self {
+            SpawnEvent::CargoShip => AppMarkerType::CargoShip,
+            SpawnEvent::PatrolHelicopter => AppMarkerType::PatrolHelicopter,
+            SpawnEvent::Chinook => AppMarkerType::Ch47,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpawnEvent::CargoShip => "Cargo ship",
+            SpawnEvent::PatrolHelicopter => "Patrol helicopter",
+            SpawnEvent::Chinook => "Chinook",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            SpawnEvent::CargoShip => "CARGO",
+            SpawnEvent::PatrolHelicopter => "HELI",
+            SpawnEvent::Chinook => "CHINOOK",
+        }
+    }
+}
+
+/// Expected window for the next spawn, as unix timestamps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpawnEstimate {
+    pub from: i64,
+    pub to: i64,
+}
+
+/// Observed spawn times of one server. Kept across wipes since event rates are a server setting.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct SpawnLog {
+    pub spawns: HashMap<SpawnEvent, Vec<i64>>,
+    /// When each connection started, spawns may have been missed before these.
+    pub connected_since: Vec<i64>,
+}
+
+impl SpawnLog {
+    pub fn record(&mut self, event: SpawnEvent, timestamp: i64) {
+        let spawns = self.spawns.entry(event).or_default();
+        spawns.push(timestamp);
+        if spawns.len() > MAX_SPAWNS {
+            spawns.drain(..spawns.len() - MAX_SPAWNS);
+        }
+    }
+
+    pub fn connected(&mut self, timestamp: i64) {
+        self.connected_since.push(timestamp);
+        if self.connected_since.len() > MAX_SPAWNS {
+            self.connected_since
+                .drain(..self.connected_since.len() - MAX_SPAWNS);
+        }
+    }
+
+    /// Whether we reconnected between two spawns, so others could have happened unseen.
+    fn straddles_gap(&self, from: i64, to: i64) -> bool {
+        self.connected_since
+            .iter()
+            .any(|connected| *connected > from && *connected <= to)
+    }
+
+    /// Uses the interquartile range of the observed intervals as the next spawn window.
+    pub fn estimate(&self, event: SpawnEvent) -> Option<SpawnEstimate> {
+        let spawns = self.spawns.get(&event)?;
+        let last = *spawns.last()?;
+
+        let mut intervals = spawns
+            .windows(2)
+            .filter(|pair| !self.straddles_gap(pair[0], pair[1]))
+            .map(|pair| pair[1] - pair[0])
+            .filter(|interval| *interval > 0 && *interval <= MAX_INTERVAL_SECS)
+            .collect::<Vec<i64>>();
+        if intervals.len() < MIN_INTERVALS {
+            return None;
+        }
+        intervals.sort();
+
+        let quartile = |q: f32| intervals[((intervals.len() - 1) as f32 * q).round() as usize];
+        Some(SpawnEstimate {
+            from: last + quartile(0.25),
+            to: last + quartile(0.75),
+        })
+    }
+}
+
+fn spawns_filename(server_id: &str) -> String {
+    format!("{}.json", server_id)
+}
+
+pub fn load_spawn_log(server_id: &str) -> Result<SpawnLog, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let spawns_dir = config_dir.join(APP_DIR_NAME).join(SPAWNS_DIR_NAME);
+    let filename = spawns_filename(server_id);
+
+    if !spawns_dir.exists() {
+        std::fs::create_dir_all(&spawns_dir)?;
+    }
+
+    let data = match std::fs::read_to_string(spawns_dir.join(&filename)) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_spawn_log] No {filename} found...");
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }?;
+
+    let spawn_log = match data {
+        Some(content) => match serde_json::from_str::<SpawnLog>(&content) {
+            Ok(spawn_log) => spawn_log,
+            Err(e) => {
+                println!("[load_spawn_log] Failed to parse {filename}: {:?}", e);
+                SpawnLog::default()
+            }
+        },
+        None => SpawnLog::default(),
+    };
+
+    Ok(spawn_log)
+}
+
+pub fn save_spawn_log(server_id: &str, spawn_log: &SpawnLog) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let spawns_dir = config_dir.join(APP_DIR_NAME).join(SPAWNS_DIR_NAME);
+
+    if !spawns_dir.exists() {
+        std::fs::create_dir_all(&spawns_dir)?;
+    }
+
+    std::fs::write(
+        spawns_dir.join(spawns_filename(server_id)),
+        serde_json::to_string_pretty(spawn_log)?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{APP_DIR_NAME, SpawnEvent};
+
+pub const GEOFENCES_DIR_NAME: &str = "geofences";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum GeofenceKind {
+    Circle,
+    Rectangle,
+}
+
+/// Zone outline in world coordinates.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum GeofenceShape {
+    Circle {
+        x: f32,
+        y: f32,
+        radius: f32,
+    },
+    Rectangle {
+        left: f32,
+        bottom: f32,
+        right: f32,
+        top: f32,
+    },
+}
+
+impl GeofenceShape {
+    /// Builds a shape from a drag on the map, circles grow from the start point.
+    pub fn from_drag(kind: GeofenceKind, start: (f32, f32), end: (f32, f32)) -> Self {
+        match kind {
+            GeofenceKind::Circle => GeofenceShape::Circle {
+                x: start.0,
+                y: start.1,
+                radius: ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt(),
+            },
+            GeofenceKind::Rectangle => GeofenceShape::Rectangle {
+                left: start.0.min(end.0),
+                bottom: start.1.min(end.1),
+                right: start.0.max(end.0),
+                top: start.1.max(end.1),
+            },
+        }
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        match *self {
+            GeofenceShape::Circle {
+                x: center_x,
+                y: center_y,
+                radius,
+            } => (x - center_x).powi(2) + (y - center_y).powi(2) <= radius.powi(2),
+            GeofenceShape::Rectangle {
+                left,
+                bottom,
+                right,
+                top,
+            } => x >= left && x <= right && y >= bottom && y <= top,
+        }
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        match *self {
+            GeofenceShape::Circle { x, y, .. } => (x, y),
+            GeofenceShape::Rectangle {
+                left,
+                bottom,
+                right,
+                top,
+            } => ((left + right) / 2.0, (bottom + top) / 2.0),
+        }
+    }
+
+    /// Zones smaller than this are treated as accidental clicks.
+    pub fn is_degenerate(&self) -> bool {
+        match *self {
+            GeofenceShape::Circle { radius, .. } => radius < 5.0,
+            GeofenceShape::Rectangle {
+                left,
+                bottom,
+                right,
+                top,
+            } => right - left < 5.0 || top - bottom < 5.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Geofence {
+    pub id: u64,
+    pub name: String,
+    pub shape: GeofenceShape,
+    /// Alert when teammates enter or leave.
+    pub team: bool,
+    /// Event markers that alert when they enter or leave.
+    pub markers: Vec<SpawnEvent>,
+}
+
+/// A geofence being drawn on the map, in world coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeofenceDraft {
+    pub kind: GeofenceKind,
+    pub start: Option<(f32, f32)>,
+    pub end: Option<(f32, f32)>,
+}
+
+impl GeofenceDraft {
+    pub fn new(kind: GeofenceKind) -> Self {
+        Self {
+            kind,
+            start: None,
+            end: None,
+        }
+    }
+
+    pub fn shape(&self) -> Option<GeofenceShape> {
+        Some(GeofenceShape::from_drag(self.kind, self.start?, self.end?))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GeofenceSubject {
+    Member(u64),
+    Marker(u32),
+}
+
+/// Remembers which subjects are inside which geofences between polls.
+#[derive(Default)]
+pub struct GeofenceTracker {
+    inside: HashSet<(u64, GeofenceSubject)>,
+    seen: HashSet<GeofenceSubject>,
+}
+
+impl GeofenceTracker {
+    /// Returns the geofences the subject entered (`true`) or left (`false`) since its last position.
+    /// The first position of a subject only sets up its state, so connecting does not raise alerts.
+    pub fn update(
+        &mut self,
+        geofences: &[Geofence],
+        subject: GeofenceSubject,
+        marker: Option<SpawnEvent>,
+        x: f32,
+        y: f32,
+    ) -> Vec<(Geofence, bool)> {
+        let first_seen = self.seen.insert(subject);
+
+        let mut changes = Vec::new();
+        for geofence in geofences {
+            let watched = match marker {
+                Some(marker) => geofence.markers.contains(&marker),
+                None => geofence.team,
+            };
+            if !watched {
+                continue;
+            }
+
+            let key = (geofence.id, subject);
+            let inside = geofence.shape.contains(x, y);
+            let changed = if inside {
+                self.inside.insert(key)
+            } else {
+                self.inside.remove(&key)
+            };
+            if changed && !first_seen {
+                changes.push((geofence.clone(), inside));
+            }
+        }
+
+        changes
+    }
+
+    pub fn forget(&mut self, subject: GeofenceSubject) {
+        self.seen.remove(&subject);
+        self.inside.retain(|(_, inside)| *inside != subject);
+    }
+
+    pub fn clear(&mut self) {
+        self.inside.clear();
+        self.seen.clear();
+    }
+}
+
+fn geofences_filename(server_id: &str, wipe_time: u32) -> String {
+    format!("{}_{}.json", server_id, wipe_time)
+}
+
+pub fn load_geofences(
+    server_id: &str,
+    wipe_time: u32,
+) -> Result<Vec<Geofence>, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let geofences_dir = config_dir.join(APP_DIR_NAME).join(GEOFENCES_DIR_NAME);
+    let filename = geofences_filename(server_id, wipe_time);
+
+    if !geofences_dir.exists() {
+        std::fs::create_dir_all(&geofences_dir)?;
+    }
+
+    let data = match std::fs::read_to_string(geofences_dir.join(&filename)) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_geofences] No {filename} found...");
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }?;
+
+    let geofences = match data {
+        Some(content) => match serde_json::from_str::<Vec<Geofence>>(&content) {
+            Ok(geofences) => geofences,
+            Err(e) => {
+                println!("[load_geofences] Failed to parse {filename}: {:?}", e);
+                vec![]
+            }
+        },
+        None => vec![],
+    };
+
+    Ok(geofences)
+}
+
+pub fn save_geofences(
+    server_id: &str,
+    wipe_time: u32,
+    geofences: &Vec<Geofence>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let geofences_dir = config_dir.join(APP_DIR_NAME).join(GEOFENCES_DIR_NAME);
+
+    if !geofences_dir.exists() {
+        std::fs::create_dir_all(&geofences_dir)?;
+    }
+
+    std::fs::write(
+        geofences_dir.join(geofences_filename(server_id, wipe_time)),
+        serde_json::to_string_pretty(geofences)?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+};
+
+use rustplus_rs::{AppMarker, AppMarkerType, AppTeamInfo};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::APP_DIR_NAME;
+
+pub const HISTORY_DIR_NAME: &str = "history";
+
+/// Minimum time between two recorded snapshots.
+pub const HISTORY_INTERVAL_SECS: i64 = 5;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryMember {
+    pub steam_id: u64,
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub is_online: bool,
+    pub is_alive: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryMarker {
+    pub id: u32,
+    pub marker_type: i32,
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+    pub radius: f32,
+    /// Fill and outline of generic radius zones as RGBA, see [`AppMarker`].
+    #[serde(default)]
+    pub color1: Option<[f32; 4]>,
+    #[serde(default)]
+    pub color2: Option<[f32; 4]>,
+    #[serde(default = "default_alpha")]
+    pub alpha: f32,
+}
+
+fn default_alpha() -> f32 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistoryDeath {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistorySnapshot {
+    pub timestamp: i64,
+    pub members: Vec<HistoryMember>,
+    pub markers: Vec<HistoryMarker>,
+    pub deaths: Vec<HistoryDeath>,
+}
+
+impl HistorySnapshot {
+    pub fn new(timestamp: i64, team_info: &AppTeamInfo, markers: &HashMap<u32, AppMarker>) -> Self {
+        Self {
+            timestamp,
+            members: team_info
+                .members
+                .iter()
+                .map(|member| HistoryMember {
+                    steam_id: member.steam_id,
+                    name: member.name.clone(),
+                    x: member.x,
+                    y: member.y,
+                    is_online: member.is_online,
+                    is_alive: member.is_alive,
+                })
+                .collect(),
+            // Vending machines barely move and carry large sell order lists, so they are left out
+            markers: markers
+                .values()
+                .filter(|marker| marker.r#type() != AppMarkerType::VendingMachine)
+                .map(|marker| HistoryMarker {
+                    id: marker.id,
+                    marker_type: marker.r#type,
+                    x: marker.x,
+                    y: marker.y,
+                    rotation: marker.rotation,
+                    radius: marker.radius,
+                    color1: marker.color1.as_ref().map(|c| [c.x, c.y, c.z, c.w]),
+                    color2: marker.color2.as_ref().map(|c| [c.x, c.y, c.z, c.w]),
+                    alpha: marker.alpha,
+                })
+                .collect(),
+            deaths: team_info
+                .map_notes
+                .iter()
+                .filter(|note| note.r#type == 0)
+                .map(|note| HistoryDeath {
+                    x: note.x,
+                    y: note.y,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn history_filename(server_id: &str, wipe_time: u32) -> String {
+    format!("{}_{}.jsonl", server_id, wipe_time)
+}
+
+pub fn load_history(
+    server_id: &str,
+    wipe_time: u32,
+) -> Result<Vec<HistorySnapshot>, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let history_dir = config_dir.join(APP_DIR_NAME).join(HISTORY_DIR_NAME);
+    let filename = history_filename(server_id, wipe_time);
+
+    if !history_dir.exists() {
+        std::fs::create_dir_all(&history_dir)?;
+    }
+
+    let file = match std::fs::File::open(history_dir.join(&filename)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_history] No {filename} found...");
+            return Ok(vec![]);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut snapshots = Vec::new();
+    for line in BufReader::new(file).lines() {
+        match serde_json::from_str::<HistorySnapshot>(&line?) {
+            Ok(snapshot) => snapshots.push(snapshot),
+            Err(e) => println!("[load_history] Skipping bad line in {filename}: {:?}", e),
+        }
+    }
+
+    Ok(snapshots)
+}
+
+/// Appends a single snapshot as one JSON line, so long wipes never rewrite the whole file.
+pub fn append_history(
+    server_id: &str,
+    wipe_time: u32,
+    snapshot: &HistorySnapshot,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let history_dir = config_dir.join(APP_DIR_NAME).join(HISTORY_DIR_NAME);
+
+    if !history_dir.exists() {
+        std::fs::create_dir_all(&history_dir)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(history_dir.join(history_filename(server_id, wipe_time)))?;
+    file.write_all(format!("{}\n", serde_json::to_string(snapshot)?).as_bytes())?;
+
+    Ok(())
+}
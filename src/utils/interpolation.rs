@@ -0,0 +1,100 @@
+use std::time::{Duration, Instant};
+
+use crate::utils::distance;
+
+/// Polls arrive once a second, so positions glide over the same span.
+pub const INTERPOLATION_DURATION: Duration = Duration::from_millis(1000);
+/// Jumps longer than this (respawns, follow target switches) snap instead of sliding across the map.
+const SNAP_DISTANCE: f32 = 400.0;
+
+/// World position with a heading in degrees.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pose {
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+}
+
+impl Pose {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            x,
+            y,
+            rotation: 0.0,
+        }
+    }
+
+    pub fn rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Blends towards `to`, turning the short way round.
+    fn lerp(&self, to: &Pose, t: f32) -> Pose {
+        let turn = (to.rotation - self.rotation + 540.0).rem_euclid(360.0) - 180.0;
+        Pose {
+            x: self.x + (to.x - self.x) * t,
+            y: self.y + (to.y - self.y) * t,
+            rotation: (self.rotation + turn * t).rem_euclid(360.0),
+        }
+    }
+}
+
+/// Glides between the last two polled poses of something on the map.
+#[derive(Clone, Debug)]
+pub struct Interpolation {
+    from: Pose,
+    to: Pose,
+    /// `None` once the pose reached `to`.
+    started: Option<Instant>,
+}
+
+impl Interpolation {
+    pub fn new(pose: Pose) -> Self {
+        Self {
+            from: pose,
+            to: pose,
+            started: None,
+        }
+    }
+
+    /// Starts gliding from wherever the pose is right now towards `target`.
+    pub fn retarget(&mut self, target: Pose, now: Instant) {
+        if target == self.to {
+            return;
+        }
+        let current = self.sample(now);
+        if distance((current.x, current.y), (target.x, target.y)) > SNAP_DISTANCE {
+            self.snap(target);
+            return;
+        }
+
+        self.from = current;
+        self.to = target;
+        self.started = Some(now);
+    }
+
+    pub fn snap(&mut self, pose: Pose) {
+        self.from = pose;
+        self.to = pose;
+        self.started = None;
+    }
+
+    pub fn sample(&mut self, now: Instant) -> Pose {
+        let Some(started) = self.started else {
+            return self.to;
+        };
+        let t = now.duration_since(started).as_secs_f32() / INTERPOLATION_DURATION.as_secs_f32();
+        if t >= 1.0 {
+            self.started = None;
+            return self.to;
+        }
+
+        self.from.lerp(&self.to, t)
+    }
+
+    /// Still moving, stays true until a sample has landed on the target.
+    pub fn is_animating(&self) -> bool {
+        self.started.is_some()
+    }
+}
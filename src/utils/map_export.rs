@@ -0,0 +1,327 @@
+use std::path::Path;
+
+use ab_glyph::FontRef;
+use freya::prelude::Bytes;
+use image::{ImageFormat, Rgba, RgbaImage, imageops::FilterType};
+use imageproc::{
+    drawing::{
+        Blend, Canvas, draw_filled_circle_mut, draw_filled_rect_mut, draw_hollow_circle_mut,
+        draw_polygon_mut, draw_text_mut, text_size,
+    },
+    point::Point,
+    rect::Rect,
+};
+use rustplus_rs::{AppMarker, AppMarkerType, app_map::Monument, app_team_info::Note};
+
+use crate::{
+    TeamMember,
+    utils::{
+        Annotation, AnnotationShape, GridPosition, MapGeometry, index_to_hex,
+        normalize_monument_name,
+    },
+};
+
+/// Square output sizes offered when exporting, in pixels.
+pub const EXPORT_RESOLUTIONS: [u32; 3] = [1024, 2048, 4096];
+
+static LABEL_FONT: &[u8] = include_bytes!("./../assets/RobotoCondensed-Variable.ttf");
+static MONUMENT_FONT: &[u8] = include_bytes!("./../assets/PermanentMarker-Regular.ttf");
+
+/// Everything drawn on an exported map, copied out of the app state so rendering can
+/// run off the UI thread.
+pub struct MapExport {
+    pub resolution: u32,
+    pub map_size: u32,
+    pub jpg_image: Bytes,
+    pub ocean_margin: i32,
+    pub monuments: Vec<Monument>,
+    pub notes: Vec<Note>,
+    pub members: Vec<TeamMember>,
+    pub markers: Vec<AppMarker>,
+    pub annotations: Vec<Annotation>,
+}
+
+fn rgba(hex: &str, alpha: u8) -> Rgba<u8> {
+    let hex = hex.trim_start_matches('#');
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+    Rgba([channel(0), channel(2), channel(4), alpha])
+}
+
+/// Text centred on `center` with a dark shadow so it stays readable on any terrain.
+fn draw_label<C: Canvas<Pixel = Rgba<u8>>>(
+    canvas: &mut C,
+    font: &FontRef,
+    size: f32,
+    center: (f32, f32),
+    text: &str,
+    color: Rgba<u8>,
+) {
+    let (width, height) = text_size(size, font, text);
+    let x = (center.0 - width as f32 / 2.0) as i32;
+    let y = (center.1 - height as f32 / 2.0) as i32;
+    let shadow = (size / 12.0).max(1.0) as i32;
+
+    draw_text_mut(
+        canvas,
+        Rgba([0, 0, 0, 160]),
+        x + shadow,
+        y + shadow,
+        size,
+        font,
+        text,
+    );
+    draw_text_mut(canvas, color, x, y, size, font, text);
+}
+
+/// Line with a width, drawn as a quad since imageproc lines are a single pixel wide.
+fn draw_thick_line<C: Canvas<Pixel = Rgba<u8>>>(
+    canvas: &mut C,
+    from: (f32, f32),
+    to: (f32, f32),
+    width: f32,
+    color: Rgba<u8>,
+) {
+    let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+    if length < 1.0 {
+        return;
+    }
+    let normal = (
+        -(to.1 - from.1) / length * width / 2.0,
+        (to.0 - from.0) / length * width / 2.0,
+    );
+    let corners = [
+        (from.0 + normal.0, from.1 + normal.1),
+        (to.0 + normal.0, to.1 + normal.1),
+        (to.0 - normal.0, to.1 - normal.1),
+        (from.0 - normal.0, from.1 - normal.1),
+    ];
+    draw_polygon(canvas, &corners, color);
+}
+
+fn draw_polygon<C: Canvas<Pixel = Rgba<u8>>>(
+    canvas: &mut C,
+    points: &[(f32, f32)],
+    color: Rgba<u8>,
+) {
+    let mut points: Vec<Point<i32>> = points
+        .iter()
+        .map(|(x, y)| Point::new(x.round() as i32, y.round() as i32))
+        .collect();
+    points.dedup();
+    // imageproc rejects explicitly closed polygons
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 {
+        return;
+    }
+    draw_polygon_mut(canvas, &points, color);
+}
+
+impl MapExport {
+    pub fn render(&self) -> Result<RgbaImage, Box<dyn std::error::Error>> {
+        let label_font = FontRef::try_from_slice(LABEL_FONT)?;
+        let monument_font = FontRef::try_from_slice(MONUMENT_FONT)?;
+
+        let base = image::load_from_memory(&self.jpg_image)?.to_rgba8();
+        let resolution = self.resolution;
+        let mut canvas = Blend(image::imageops::resize(
+            &base,
+            resolution,
+            resolution,
+            FilterType::Triangle,
+        ));
+
+        // The JPEG has its own pixel scale, the margin is converted to world units so
+        // the shared geometry can place everything
+        let world_scale =
+            (base.width() as f32 - self.ocean_margin as f32 * 2.0) / self.map_size as f32;
+        let geometry =
+            MapGeometry::new(self.map_size as f32, self.ocean_margin as f32 / world_scale);
+        let px = resolution as f32 / geometry.content_size();
+        let to_px = |x: f32, y: f32| {
+            let (left, top) = geometry.world_to_content(x, y);
+            (left * px, top * px)
+        };
+        // Text and markers scale with the output rather than the map so exports read the same
+        let unit = resolution as f32 / 1024.0;
+
+        self.draw_grid(&mut canvas, &label_font, &geometry, px, unit);
+
+        for monument in &self.monuments {
+            if monument.token == "train_tunnel_display_name" || monument.token.starts_with("assets")
+            {
+                continue;
+            }
+            draw_label(
+                &mut canvas,
+                &monument_font,
+                11.0 * unit,
+                to_px(monument.x, monument.y),
+                &normalize_monument_name(monument.token.clone()),
+                rgba("#191919", 230),
+            );
+        }
+
+        for marker in &self.markers {
+            if marker.r#type() != AppMarkerType::VendingMachine {
+                continue;
+            }
+            let (x, y) = to_px(marker.x, marker.y);
+            let size = 6.0 * unit;
+            draw_filled_rect_mut(
+                &mut canvas,
+                Rect::at((x - size / 2.0) as i32, (y - size / 2.0) as i32)
+                    .of_size(size.max(1.0) as u32, size.max(1.0) as u32),
+                if marker.sell_orders.is_empty() {
+                    rgba("#605B55", 255)
+                } else {
+                    rgba("#79ab3a", 255)
+                },
+            );
+        }
+
+        for annotation in &self.annotations {
+            let (color, color_muted) = index_to_hex(annotation.color);
+            if let AnnotationShape::Polygon { points } = &annotation.shape {
+                let points: Vec<(f32, f32)> = points.iter().map(|(x, y)| to_px(*x, *y)).collect();
+                draw_polygon(&mut canvas, &points, rgba(color_muted, 110));
+            }
+            for (from, to) in annotation.shape.segments() {
+                draw_thick_line(
+                    &mut canvas,
+                    to_px(from.0, from.1),
+                    to_px(to.0, to.1),
+                    2.0 * unit,
+                    rgba(color, 230),
+                );
+            }
+            let (x, y) = annotation.shape.anchor();
+            let center = to_px(x, y);
+            draw_filled_circle_mut(
+                &mut canvas,
+                (center.0 as i32, center.1 as i32),
+                (5.0 * unit) as i32,
+                rgba(color, 255),
+            );
+            if !annotation.label.is_empty() {
+                draw_label(
+                    &mut canvas,
+                    &label_font,
+                    12.0 * unit,
+                    (center.0, center.1 + 12.0 * unit),
+                    &annotation.label,
+                    rgba("#E4DAD1", 255),
+                );
+            }
+        }
+
+        // Type 0 notes are deaths, they clutter a planning map
+        for note in self.notes.iter().filter(|note| note.r#type != 0) {
+            let (color, color_muted) = index_to_hex(note.colour_index);
+            let center = to_px(note.x, note.y);
+            let center_i = (center.0 as i32, center.1 as i32);
+            draw_filled_circle_mut(
+                &mut canvas,
+                center_i,
+                (6.0 * unit) as i32,
+                rgba(color_muted, 255),
+            );
+            draw_hollow_circle_mut(&mut canvas, center_i, (6.0 * unit) as i32, rgba(color, 255));
+            if !note.label.is_empty() {
+                draw_label(
+                    &mut canvas,
+                    &label_font,
+                    12.0 * unit,
+                    (center.0, center.1 + 14.0 * unit),
+                    &note.label,
+                    rgba(color, 255),
+                );
+            }
+        }
+
+        for member in &self.members {
+            let center = to_px(member.x, member.y);
+            let color = match (member.is_online, member.is_alive) {
+                (_, false) => rgba("#c03939", 255),
+                (true, true) => rgba("#79ab3a", 255),
+                (false, true) => rgba("#605B55", 255),
+            };
+            let center_i = (center.0 as i32, center.1 as i32);
+            draw_filled_circle_mut(
+                &mut canvas,
+                center_i,
+                (6.0 * unit) as i32,
+                rgba("#0E0E0D", 255),
+            );
+            draw_filled_circle_mut(&mut canvas, center_i, (4.5 * unit) as i32, color);
+            draw_label(
+                &mut canvas,
+                &label_font,
+                13.0 * unit,
+                (center.0, center.1 - 14.0 * unit),
+                &member.name,
+                rgba("#E4DAD1", 255),
+            );
+        }
+
+        Ok(canvas.0)
+    }
+
+    fn draw_grid<C: Canvas<Pixel = Rgba<u8>>>(
+        &self,
+        canvas: &mut C,
+        font: &FontRef,
+        geometry: &MapGeometry,
+        px: f32,
+        unit: f32,
+    ) {
+        let color = rgba("#191919", 128);
+        let cells = geometry.cells();
+        let cell = geometry.cell_size() * px;
+        let start = geometry.margin * px;
+        let length = geometry.world_size * px;
+        let thickness = (1.5 * unit).max(1.0);
+
+        for i in 0..=cells {
+            let offset = start + i as f32 * cell - thickness / 2.0;
+            draw_filled_rect_mut(
+                canvas,
+                Rect::at(offset as i32, start as i32).of_size(thickness as u32, length as u32),
+                color,
+            );
+            draw_filled_rect_mut(
+                canvas,
+                Rect::at(start as i32, offset as i32).of_size(length as u32, thickness as u32),
+                color,
+            );
+        }
+
+        let size = (cell * 0.22).min(14.0 * unit);
+        for column in 0..cells {
+            for row in 0..cells {
+                let reference = GridPosition {
+                    column,
+                    row,
+                    offset: (0.0, 0.0),
+                }
+                .to_string();
+                draw_text_mut(
+                    canvas,
+                    color,
+                    (start + column as f32 * cell + 3.0 * unit) as i32,
+                    (start + row as f32 * cell + 3.0 * unit) as i32,
+                    size,
+                    font,
+                    &reference,
+                );
+            }
+        }
+    }
+}
+
+pub fn export_map_image(path: &Path, export: &MapExport) -> Result<(), Box<dyn std::error::Error>> {
+    export.render()?.save_with_format(path, ImageFormat::Png)?;
+
+    Ok(())
+}
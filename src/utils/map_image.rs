@@ -0,0 +1,226 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Cursor,
+    path::PathBuf,
+};
+
+use freya::prelude::Bytes;
+use image::{ImageFormat, imageops::FilterType};
+use rustplus_rs::{AppMap, app_map::Monument};
+use serde::{Deserialize, Serialize};
+
+use crate::{MapState, utils::APP_DIR_NAME};
+
+const MAP_CACHE_DIR_NAME: &str = "maps";
+
+/// Number of levels kept, the full image included.
+const LEVEL_COUNT: usize = 4;
+/// Levels stop halving once they would get smaller than this.
+const MIN_LEVEL_SIZE: u32 = 512;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapImageLevel {
+    /// The viewer caches decoded images by key, so every map gets its own.
+    pub key: String,
+    pub size: u32,
+    pub bytes: Bytes,
+}
+
+/// Map JPEG plus downscaled copies, shared between every map view without copying.
+#[derive(Clone, Debug)]
+pub struct MapImage {
+    /// Largest first, the first level is the image the server sent.
+    levels: Vec<MapImageLevel>,
+}
+
+impl MapImage {
+    /// Decodes the map once and encodes a level at every halving down to `MIN_LEVEL_SIZE`.
+    pub fn from_jpeg(jpg_image: Vec<u8>) -> Result<Self, Box<dyn std::error::Error>> {
+        let image = image::load_from_memory_with_format(&jpg_image, ImageFormat::Jpeg)?.to_rgb8();
+        // Servers and wipes differ by image, so the hash keeps stale maps out of the cache
+        let mut hasher = DefaultHasher::new();
+        jpg_image.hash(&mut hasher);
+        let key = format!("map-{:016x}", hasher.finish());
+
+        let mut levels = vec![MapImageLevel {
+            key: key.clone(),
+            size: image.width(),
+            bytes: Bytes::from(jpg_image),
+        }];
+
+        let mut size = image.width() / 2;
+        for level in 1..LEVEL_COUNT {
+            if size < MIN_LEVEL_SIZE {
+                break;
+            }
+            let mut bytes = Vec::new();
+            image::imageops::resize(&image, size, size, FilterType::Triangle)
+                .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Jpeg)?;
+            levels.push(MapImageLevel {
+                key: format!("{key}-mip-{level}"),
+                size,
+                bytes: Bytes::from(bytes),
+            });
+            size /= 2;
+        }
+
+        Ok(Self { levels })
+    }
+
+    /// Identifies the image, equal keys mean the same map.
+    pub fn key(&self) -> &str {
+        &self.levels[0].key
+    }
+
+    /// The JPEG exactly as the server sent it.
+    pub fn original(&self) -> &Bytes {
+        &self.levels[0].bytes
+    }
+
+    /// Smallest level that still covers `size` screen pixels.
+    pub fn level_for(&self, size: f32) -> &MapImageLevel {
+        self.levels
+            .iter()
+            .rev()
+            .find(|level| level.size as f32 >= size)
+            .unwrap_or(&self.levels[0])
+    }
+}
+
+impl MapState {
+    pub fn from_app_map(map: AppMap) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            width: map.width,
+            height: map.height,
+            image: Some(MapImage::from_jpeg(map.jpg_image)?),
+            ocean_margin: map.ocean_margin,
+            monuments: map.monuments,
+            background: Some(map.background),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedMonument {
+    token: String,
+    x: f32,
+    y: f32,
+}
+
+/// Everything of `AppMap` except the JPEG, which is kept next to it as its own file.
+#[derive(Serialize, Deserialize)]
+struct CachedMap {
+    width: u32,
+    height: u32,
+    ocean_margin: i32,
+    background: String,
+    monuments: Vec<CachedMonument>,
+}
+
+fn map_cache_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let cache_dir = match dirs::cache_dir() {
+        Some(cache_dir) => cache_dir.join(APP_DIR_NAME).join(MAP_CACHE_DIR_NAME),
+        None => PathBuf::from(".cache").join(MAP_CACHE_DIR_NAME),
+    };
+
+    std::fs::create_dir_all(&cache_dir)?;
+    Ok(cache_dir)
+}
+
+fn map_cache_filename(server_id: &str, wipe_time: u32) -> String {
+    format!("{}_{}", server_id, wipe_time)
+}
+
+/// Map saved for this server and wipe, so reconnecting doesn't have to fetch it again.
+pub fn load_cached_map(
+    server_id: &str,
+    wipe_time: u32,
+) -> Result<Option<AppMap>, Box<dyn std::error::Error>> {
+    let cache_dir = map_cache_dir()?;
+    let filename = map_cache_filename(server_id, wipe_time);
+
+    let (metadata, jpg_image) = match (
+        std::fs::read_to_string(cache_dir.join(format!("{filename}.json"))),
+        std::fs::read(cache_dir.join(format!("{filename}.jpg"))),
+    ) {
+        (Ok(metadata), Ok(jpg_image)) => (metadata, jpg_image),
+        (Err(e), _) | (_, Err(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_cached_map] No {filename} found...");
+            return Ok(None);
+        }
+        (Err(e), _) | (_, Err(e)) => return Err(e.into()),
+    };
+
+    let cached = match serde_json::from_str::<CachedMap>(&metadata) {
+        Ok(cached) => cached,
+        Err(e) => {
+            println!("[load_cached_map] Failed to parse {filename}: {:?}", e);
+            return Ok(None);
+        }
+    };
+
+    Ok(Some(AppMap {
+        width: cached.width,
+        height: cached.height,
+        jpg_image,
+        ocean_margin: cached.ocean_margin,
+        monuments: cached
+            .monuments
+            .into_iter()
+            .map(|monument| Monument {
+                token: monument.token,
+                x: monument.x,
+                y: monument.y,
+            })
+            .collect(),
+        background: cached.background,
+    }))
+}
+
+/// Stores the map for this wipe and drops maps of the server's earlier wipes.
+pub fn save_cached_map(
+    server_id: &str,
+    wipe_time: u32,
+    map: &AppMap,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cache_dir = map_cache_dir()?;
+    let filename = map_cache_filename(server_id, wipe_time);
+
+    for entry in std::fs::read_dir(&cache_dir)? {
+        let path = entry?.path();
+        let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if stem != filename
+            && stem
+                .strip_prefix(server_id)
+                .is_some_and(|s| s.starts_with('_'))
+        {
+            std::fs::remove_file(&path)?;
+        }
+    }
+
+    let cached = CachedMap {
+        width: map.width,
+        height: map.height,
+        ocean_margin: map.ocean_margin,
+        background: map.background.clone(),
+        monuments: map
+            .monuments
+            .iter()
+            .map(|monument| CachedMonument {
+                token: monument.token.clone(),
+                x: monument.x,
+                y: monument.y,
+            })
+            .collect(),
+    };
+
+    std::fs::write(cache_dir.join(format!("{filename}.jpg")), &map.jpg_image)?;
+    std::fs::write(
+        cache_dir.join(format!("{filename}.json")),
+        serde_json::to_string_pretty(&cached)?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+/// Rough sustained travel speeds in metres per second.
+pub const TRAVEL_MODES: [(&str, f32); 3] = [("FOOT", 5.5), ("HORSE", 12.0), ("MINICOPTER", 30.0)];
+
+pub fn distance(from: (f32, f32), to: (f32, f32)) -> f32 {
+    ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt()
+}
+
+/// Length of every leg of a route, in world units which are metres.
+pub fn route_segments(points: &[(f32, f32)]) -> Vec<f32> {
+    points
+        .windows(2)
+        .map(|pair| distance(pair[0], pair[1]))
+        .collect()
+}
+
+pub fn format_distance(metres: f32) -> String {
+    if metres >= 1000.0 {
+        format!("{:.2}km", metres / 1000.0)
+    } else {
+        format!("{:.0}m", metres)
+    }
+}
+
+pub fn format_travel_time(metres: f32, speed: f32) -> String {
+    let secs = (metres / speed).round() as i64;
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s => format!("{}m {:02}s", s / 60, s % 60),
+    }
+}
+
+/// Compass bearing in degrees from `from` to `to`, 0 is north and it grows clockwise.
+pub fn bearing(from: (f32, f32), to: (f32, f32)) -> f32 {
+    (to.0 - from.0).atan2(to.1 - from.1).to_degrees().rem_euclid(360.0)
+}
+
+pub fn compass_point(bearing: f32) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((bearing / 45.0).round() as usize) % POINTS.len()]
+}
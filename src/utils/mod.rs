@@ -12,8 +12,38 @@ mod rustplus_poller;
 pub use rustplus_poller::*;
 mod text_utils;
 pub use text_utils::*;
+mod coordinates;
+pub use coordinates::*;
 mod steam_utils;
 pub use steam_utils::*;
+mod timeline;
+pub use timeline::*;
+mod trails;
+pub use trails::*;
+mod history;
+pub use history::*;
+mod wipe;
+pub use wipe::*;
+mod population;
+pub use population::*;
+mod event_timers;
+pub use event_timers::*;
+mod geofences;
+pub use geofences::*;
+mod annotations;
+pub use annotations::*;
+mod measure;
+pub use measure::*;
+mod targets;
+pub use targets::*;
+mod monuments;
+pub use monuments::*;
+mod map_export;
+pub use map_export::*;
+mod map_image;
+pub use map_image::*;
+mod interpolation;
+pub use interpolation::*;
 
 use crate::components::{Timeout, Toast};
 use freya::{prelude::EventHandler, radio::Writable};
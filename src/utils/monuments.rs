@@ -0,0 +1,45 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use rustplus_rs::app_map::Monument;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::distance;
+
+static MONUMENT_DATA: &[u8] = include_bytes!("./../assets/monument_data.json");
+
+/// Bundled static info about monuments, keyed by the same tokens the server sends.
+static MONUMENT_INFO: LazyLock<HashMap<String, MonumentInfo>> = LazyLock::new(|| {
+    serde_json::from_slice(MONUMENT_DATA).expect("Monument data should be loaded")
+});
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MonumentInfo {
+    pub radiation: String,
+    /// Keycard colours needed to get through every puzzle room.
+    pub keycards: Vec<String>,
+    /// Whether the puzzle needs an electric fuse.
+    pub fuse: bool,
+    pub recycler: bool,
+    /// Workbench level available to players, if any.
+    pub workbench: Option<u8>,
+    pub crates: Vec<String>,
+    pub safe_zone: bool,
+}
+
+pub fn monument_info(token: &str) -> Option<&'static MonumentInfo> {
+    MONUMENT_INFO.get(token)
+}
+
+/// Closest monument within `radius` world units of `point`, skipping the ones the map doesn't draw.
+pub fn monument_at(point: (f32, f32), radius: f32, monuments: &[Monument]) -> Option<Monument> {
+    monuments
+        .iter()
+        .filter(|monument| {
+            monument.token != "train_tunnel_display_name" && !monument.token.starts_with("assets")
+        })
+        .map(|monument| (monument, distance(point, (monument.x, monument.y))))
+        .filter(|(_, distance)| *distance <= radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(monument, _)| monument.clone())
+}
@@ -0,0 +1,103 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::APP_DIR_NAME;
+
+pub const POPULATION_DIR_NAME: &str = "population";
+
+/// How far back population samples are kept, older ones are pruned from the file on load.
+pub const POPULATION_RETENTION_SECS: i64 = 7 * 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PopulationSample {
+    pub timestamp: i64,
+    pub players: u32,
+    pub max_players: u32,
+    pub queued_players: u32,
+}
+
+fn population_filename(server_id: &str) -> String {
+    format!("{}.jsonl", server_id)
+}
+
+pub fn load_population(server_id: &str) -> Result<Vec<PopulationSample>, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let population_dir = config_dir.join(APP_DIR_NAME).join(POPULATION_DIR_NAME);
+    let filename = population_filename(server_id);
+
+    if !population_dir.exists() {
+        std::fs::create_dir_all(&population_dir)?;
+    }
+
+    let file = match std::fs::File::open(population_dir.join(&filename)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_population] No {filename} found...");
+            return Ok(vec![]);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let cutoff = chrono::Utc::now().timestamp() - POPULATION_RETENTION_SECS;
+    let mut samples = Vec::new();
+    let mut pruned = false;
+    for line in BufReader::new(file).lines() {
+        match serde_json::from_str::<PopulationSample>(&line?) {
+            Ok(sample) if sample.timestamp >= cutoff => samples.push(sample),
+            Ok(_) => pruned = true,
+            Err(e) => {
+                println!("[load_population] Skipping bad line in {filename}: {:?}", e);
+                pruned = true;
+            }
+        }
+    }
+
+    if pruned {
+        rewrite_population(&population_dir.join(&filename), &samples)?;
+    }
+
+    Ok(samples)
+}
+
+/// Replaces the file with only the retained samples, written aside first so a crash
+/// can't leave it half written.
+fn rewrite_population(
+    path: &Path,
+    samples: &[PopulationSample],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_path = path.with_extension("jsonl.tmp");
+    let mut contents = String::new();
+    for sample in samples {
+        contents.push_str(&serde_json::to_string(sample)?);
+        contents.push('\n');
+    }
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+pub fn append_population(
+    server_id: &str,
+    sample: &PopulationSample,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let population_dir = config_dir.join(APP_DIR_NAME).join(POPULATION_DIR_NAME);
+
+    if !population_dir.exists() {
+        std::fs::create_dir_all(&population_dir)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(population_dir.join(population_filename(server_id)))?;
+    file.write_all(format!("{}\n", serde_json::to_string(sample)?).as_bytes())?;
+
+    Ok(())
+}
@@ -3,13 +3,13 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use futures_channel::mpsc::UnboundedSender;
 use rustplus_rs::RustPlus;
 
-use crate::ChannelSend;
-use crate::utils::ServerData;
+use crate::utils::{ServerData, load_cached_map, save_cached_map};
+use crate::{ChannelSend, MapState};
 
 pub struct Poller {
     details: Arc<Mutex<Option<ServerData>>>,
@@ -19,6 +19,7 @@ pub struct Poller {
 }
 
 const POLL_INTERVAL_MS: u64 = 1000;
+const INFO_POLL_INTERVAL_MS: u64 = 60_000;
 
 impl Poller {
     pub fn new(details: Option<ServerData>, state_tx: UnboundedSender<ChannelSend>) -> Self {
@@ -100,15 +101,26 @@ impl Poller {
                 }
                 println!("Connected to server");
 
+                // Team chat arrives as a broadcast, it isn't part of the polled state
+                let chat_tx = state_tx.clone();
+                rustplus.on_team_message(move |team_message| {
+                    let _ = chat_tx.unbounded_send(ChannelSend::TeamMessage {
+                        steam_id: team_message.steam_id,
+                        name: team_message.name,
+                        message: team_message.message,
+                    });
+                });
+
                 let get_info = rustplus.get_info();
-                let get_map = rustplus.get_map();
                 let get_map_markers = rustplus.get_map_markers();
                 let get_team_info = rustplus.get_team_info();
 
+                let mut wipe_time = None;
                 match get_info.await {
                     Ok(info) => {
                         // std::fs::write("info.json", serde_json::to_string_pretty(&info).unwrap())
                         //     .unwrap();
+                        wipe_time = Some(info.wipe_time);
                         state_tx
                             .unbounded_send(ChannelSend::InfoStateUpdate(Some(info.clone())))
                             .unwrap();
@@ -122,13 +134,41 @@ impl Poller {
                     }
                 }
 
-                match get_map.await {
+                // The map only changes on wipe, so a cached copy saves fetching several megabytes
+                let cached_map = wipe_time.and_then(|wipe_time| {
+                    match load_cached_map(&server_data.id, wipe_time) {
+                        Ok(map) => map,
+                        Err(e) => {
+                            println!("Error loading cached map: {:?}", e);
+                            None
+                        }
+                    }
+                });
+                let map = match cached_map {
+                    Some(map) => Ok(map),
+                    None => rustplus.get_map().await.inspect(|map| {
+                        if let Some(wipe_time) = wipe_time
+                            && let Err(e) = save_cached_map(&server_data.id, wipe_time, map)
+                        {
+                            println!("Error caching map: {:?}", e);
+                        }
+                    }),
+                };
+
+                match map {
                     Ok(map) => {
                         // std::fs::write("map.json", serde_json::to_string_pretty(&map).unwrap())
                         //     .unwrap();
-                        state_tx
-                            .unbounded_send(ChannelSend::MapStateUpdate(Some(map.clone())))
-                            .unwrap();
+                        match MapState::from_app_map(map) {
+                            Ok(map_state) => {
+                                state_tx
+                                    .unbounded_send(ChannelSend::MapStateUpdate(Some(map_state)))
+                                    .unwrap();
+                            }
+                            Err(e) => {
+                                println!("Error: Failed to decode map image: {:?}", e);
+                            }
+                        }
                     }
                     Err(e) => {
                         let err_msg = format!("Failed to get map data: {}", e);
@@ -179,7 +219,24 @@ impl Poller {
                     }
                 }
 
+                let mut last_info_poll = Instant::now();
                 while !stop_flag.load(Ordering::SeqCst) {
+                    // Info barely changes, so only refresh it for the population history
+                    if last_info_poll.elapsed() >= Duration::from_millis(INFO_POLL_INTERVAL_MS) {
+                        last_info_poll = Instant::now();
+                        match rustplus.get_info().await {
+                            Ok(info) => {
+                                state_tx
+                                    .unbounded_send(ChannelSend::InfoStateUpdate(Some(info)))
+                                    .unwrap();
+                            }
+                            Err(e) => {
+                                let err_msg = format!("Failed to get info: {}", e);
+                                println!("Error: {}", err_msg);
+                            }
+                        }
+                    }
+
                     let get_map_markers = rustplus.get_map_markers();
                     let get_team_info = rustplus.get_team_info();
 
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::pages::{MinimapSettings, UserData};
+use crate::pages::{MinimapPresets, MinimapSettings, NotificationSettings, UserData};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ServerData {
@@ -41,6 +41,8 @@ pub const APP_DIR_NAME: &str = "OxidePlus";
 const SERVERS_FILENAME: &str = "servers.json";
 const APP_DATA_FILENAME: &str = "user_data.json";
 const MINIMAP_SETTINGS_FILENAME: &str = "config.json";
+const MINIMAP_PRESETS_FILENAME: &str = "minimap_presets.json";
+const NOTIFICATION_SETTINGS_FILENAME: &str = "notifications.json";
 
 pub fn load_servers() -> Result<Vec<ServerData>, Box<dyn std::error::Error>> {
     let config_dir = dirs::config_dir().unwrap();
@@ -524,4 +526,98 @@ pub fn save_minimap_settings(minimap_settings: &MinimapSettings) -> Result<(), B
     std::fs::write(config_path, serde_json::to_string_pretty(&minimap_settings)?)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn load_minimap_presets() -> Result<Option<MinimapPresets>, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let config_path = config_dir.join(APP_DIR_NAME).join(MINIMAP_PRESETS_FILENAME);
+
+    if !config_path.exists() {
+        std::fs::create_dir_all(config_dir.join(APP_DIR_NAME))?;
+    }
+
+    let data = match std::fs::read_to_string(&config_path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_minimap_presets] No {MINIMAP_PRESETS_FILENAME} found...");
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }?;
+
+    let minimap_presets = match data {
+        Some(content) => match serde_json::from_str::<MinimapPresets>(&content) {
+            Ok(minimap_presets) => Some(minimap_presets),
+            Err(e) => {
+                println!(
+                    "[load_minimap_presets] Failed to parse {MINIMAP_PRESETS_FILENAME}: {:?}",
+                    e
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    Ok(minimap_presets)
+}
+
+pub fn save_minimap_presets(minimap_presets: &MinimapPresets) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let config_path = config_dir.join(APP_DIR_NAME).join(MINIMAP_PRESETS_FILENAME);
+
+    if !config_path.exists() {
+        std::fs::create_dir_all(config_dir.join(APP_DIR_NAME))?;
+    }
+
+    std::fs::write(config_path, serde_json::to_string_pretty(minimap_presets)?)?;
+
+    Ok(())
+}
+
+pub fn load_notification_settings() -> Result<Option<NotificationSettings>, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let config_path = config_dir.join(APP_DIR_NAME).join(NOTIFICATION_SETTINGS_FILENAME);
+
+    if !config_path.exists() {
+        std::fs::create_dir_all(config_dir.join(APP_DIR_NAME))?;
+    }
+
+    let data = match std::fs::read_to_string(&config_path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_notification_settings] No {NOTIFICATION_SETTINGS_FILENAME} found...");
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }?;
+
+    let notification_settings = match data {
+        Some(content) => match serde_json::from_str::<NotificationSettings>(&content) {
+            Ok(notification_settings) => Some(notification_settings),
+            Err(e) => {
+                println!(
+                    "[load_notification_settings] Failed to parse {NOTIFICATION_SETTINGS_FILENAME}: {:?}",
+                    e
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    return Ok(notification_settings);
+}
+
+pub fn save_notification_settings(notification_settings: &NotificationSettings) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let config_path = config_dir.join(APP_DIR_NAME).join(NOTIFICATION_SETTINGS_FILENAME);
+
+    if !config_path.exists() {
+        std::fs::create_dir_all(config_dir.join(APP_DIR_NAME))?;
+    }
+
+    std::fs::write(config_path, serde_json::to_string_pretty(notification_settings)?)?;
+
+    Ok(())
+}
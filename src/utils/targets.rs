@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use rustplus_rs::{AppMarker, AppMarkerType, app_map::Monument, app_team_info::Note};
+
+use crate::{
+    TeamMember,
+    utils::{SpawnEvent, bearing, compass_point, distance, format_distance, normalize_monument_name},
+};
+
+/// Something on the map that can be pointed at. Teammates and markers are kept by id
+/// so their position follows the live data, everything else is a fixed place.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MapTarget {
+    Member(u64),
+    Marker(u32),
+    Place { name: String, x: f32, y: f32 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedTarget {
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+}
+
+impl MapTarget {
+    /// Current name and position, `None` once the teammate left or the marker despawned.
+    pub fn resolve(
+        &self,
+        members: &HashMap<u64, TeamMember>,
+        markers: &HashMap<u32, AppMarker>,
+    ) -> Option<ResolvedTarget> {
+        match self {
+            MapTarget::Member(steam_id) => members.get(steam_id).map(|member| ResolvedTarget {
+                name: member.name.clone(),
+                x: member.x,
+                y: member.y,
+            }),
+            MapTarget::Marker(id) => markers.get(id).map(|marker| ResolvedTarget {
+                name: marker_name(marker),
+                x: marker.x,
+                y: marker.y,
+            }),
+            MapTarget::Place { name, x, y } => Some(ResolvedTarget {
+                name: name.clone(),
+                x: *x,
+                y: *y,
+            }),
+        }
+    }
+}
+
+pub fn marker_name(marker: &AppMarker) -> String {
+    if let Some(event) = SpawnEvent::from_marker_type(marker.r#type()) {
+        return event.name().to_string();
+    }
+    match marker.r#type() {
+        AppMarkerType::VendingMachine if !marker.name.is_empty() => marker.name.clone(),
+        AppMarkerType::VendingMachine => "Vending machine".to_string(),
+        AppMarkerType::Explosion => "Explosion".to_string(),
+        AppMarkerType::GenericRadius => "Event zone".to_string(),
+        AppMarkerType::Player => "Player".to_string(),
+        marker_type => format!("{:?}", marker_type),
+    }
+}
+
+/// Closest target within `radius` world units of `point`, teammates win over markers,
+/// which win over notes and monuments.
+pub fn target_at(
+    point: (f32, f32),
+    radius: f32,
+    members: &HashMap<u64, TeamMember>,
+    markers: &HashMap<u32, AppMarker>,
+    notes: &[Note],
+    monuments: &[Monument],
+) -> Option<MapTarget> {
+    fn closest<T>(
+        point: (f32, f32),
+        radius: f32,
+        items: impl Iterator<Item = (T, f32, f32)>,
+    ) -> Option<T> {
+        items
+            .map(|(item, x, y)| (item, distance(point, (x, y))))
+            .filter(|(_, distance)| *distance <= radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(item, _)| item)
+    }
+
+    closest(
+        point,
+        radius,
+        members
+            .values()
+            .map(|member| (MapTarget::Member(member.steam_id), member.x, member.y)),
+    )
+    .or_else(|| {
+        closest(
+            point,
+            radius,
+            markers
+                .values()
+                .filter(|marker| marker.r#type() != AppMarkerType::GenericRadius)
+                .map(|marker| (MapTarget::Marker(marker.id), marker.x, marker.y)),
+        )
+    })
+    .or_else(|| {
+        closest(
+            point,
+            radius,
+            notes.iter().map(|note| {
+                let name = match (note.r#type, note.label.is_empty()) {
+                    (0, _) => "Death".to_string(),
+                    (_, true) => "Map note".to_string(),
+                    (_, false) => note.label.clone(),
+                };
+                (
+                    MapTarget::Place {
+                        name,
+                        x: note.x,
+                        y: note.y,
+                    },
+                    note.x,
+                    note.y,
+                )
+            }),
+        )
+    })
+    .or_else(|| {
+        closest(
+            point,
+            radius,
+            monuments.iter().map(|monument| {
+                (
+                    MapTarget::Place {
+                        name: normalize_monument_name(monument.token.clone()),
+                        x: monument.x,
+                        y: monument.y,
+                    },
+                    monument.x,
+                    monument.y,
+                )
+            }),
+        )
+    })
+}
+
+/// Monuments further away than this are not worth naming, "near" would be misleading.
+const NEARBY_MONUMENT_RANGE: f32 = 600.0;
+/// Close enough to a monument's centre to be considered inside it.
+const AT_MONUMENT_RANGE: f32 = 40.0;
+
+/// Tokens that show up dozens of times per map and don't help anyone find a spot.
+const UNHELPFUL_MONUMENTS: [&str; 3] = [
+    "train_tunnel_display_name",
+    "train_tunnel_link_display_name",
+    "DungeonBase",
+];
+
+/// Human readable location relative to the closest monument, such as
+/// "near Launch Site (120m NE)", `None` when nothing notable is in range.
+pub fn describe_location(x: f32, y: f32, monuments: &[Monument]) -> Option<String> {
+    let (monument, metres) = monuments
+        .iter()
+        .filter(|monument| !UNHELPFUL_MONUMENTS.contains(&monument.token.as_str()))
+        .map(|monument| (monument, distance((monument.x, monument.y), (x, y))))
+        .filter(|(_, metres)| *metres <= NEARBY_MONUMENT_RANGE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let name = normalize_monument_name(monument.token.clone());
+    if metres <= AT_MONUMENT_RANGE {
+        return Some(format!("at {}", name));
+    }
+
+    Some(format!(
+        "near {} ({} {})",
+        name,
+        format_distance(metres),
+        compass_point(bearing((monument.x, monument.y), (x, y)))
+    ))
+}
@@ -1,20 +1,6 @@
 use capitalize::Capitalize;
 use freya::prelude::{Bytes, Color};
 
-pub fn number_to_letters(n: u32) -> String {
-    let mut result = String::new();
-    let mut num = n + 1;
-
-    while num > 0 {
-        num -= 1;
-        let remainder = (num % 26) as u8;
-        result.insert(0, (b'A' + remainder) as char);
-        num /= 26;
-    }
-
-    result
-}
-
 pub fn normalize_monument_name(name: String) -> String {
     let regex = regex::Regex::new(r"([A-Z])").unwrap();
 
@@ -70,35 +56,26 @@ pub fn normalize_monument_name(name: String) -> String {
     }
 }
 
-pub fn index_to_color(index: i32) -> (Color, Color) {
+/// Note colour and its muted background as hex strings, for places that don't draw with freya.
+pub fn index_to_hex(index: i32) -> (&'static str, &'static str) {
     match index {
-        1 => (
-            Color::from_hex("#3075ca").unwrap(),
-            Color::from_hex("#12233e").unwrap(),
-        ),
-        2 => (
-            Color::from_hex("#79ab3a").unwrap(),
-            Color::from_hex("#243410").unwrap(),
-        ),
-        3 => (
-            Color::from_hex("#c03939").unwrap(),
-            Color::from_hex("#3a110f").unwrap(),
-        ),
-        4 => (
-            Color::from_hex("#af59bc").unwrap(),
-            Color::from_hex("#361d39").unwrap(),
-        ),
-        5 => (
-            Color::from_hex("#05eec3").unwrap(),
-            Color::from_hex("#044c3d").unwrap(),
-        ),
-        _ => (
-            Color::from_hex("#d2d456").unwrap(),
-            Color::from_hex("#444518").unwrap(),
-        ),
+        1 => ("#3075ca", "#12233e"),
+        2 => ("#79ab3a", "#243410"),
+        3 => ("#c03939", "#3a110f"),
+        4 => ("#af59bc", "#361d39"),
+        5 => ("#05eec3", "#044c3d"),
+        _ => ("#d2d456", "#444518"),
     }
 }
 
+pub fn index_to_color(index: i32) -> (Color, Color) {
+    let (color, color_muted) = index_to_hex(index);
+    (
+        Color::from_hex(color).unwrap(),
+        Color::from_hex(color_muted).unwrap(),
+    )
+}
+
 pub fn index_to_icon(index: i32) -> Bytes {
     match index {
         1 => Bytes::from_static(include_bytes!("../assets/MDI/currency-usd.svg")),
@@ -0,0 +1,188 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+};
+
+use rustplus_rs::app_map::Monument;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::APP_DIR_NAME;
+
+pub const TIMELINE_DIR_NAME: &str = "timeline";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TimelineEventKind {
+    TeammateDied { steam_id: u64, name: String },
+    TeammateSpawned { steam_id: u64, name: String },
+    TeammateOnline { steam_id: u64, name: String },
+    TeammateOffline { steam_id: u64, name: String },
+    CargoShip { marker_id: u32 },
+    PatrolHelicopter { marker_id: u32 },
+    Chinook { marker_id: u32 },
+    Explosion { marker_id: u32 },
+    Alarm { title: String, message: String },
+    Geofence { zone: String, subject: String, entered: bool },
+    ChatMention { steam_id: u64, name: String, message: String },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimelineCategory {
+    Team,
+    Events,
+    Alarms,
+    Chat,
+}
+
+impl TimelineEventKind {
+    pub fn category(&self) -> TimelineCategory {
+        match self {
+            TimelineEventKind::TeammateDied { .. }
+            | TimelineEventKind::TeammateSpawned { .. }
+            | TimelineEventKind::TeammateOnline { .. }
+            | TimelineEventKind::TeammateOffline { .. } => TimelineCategory::Team,
+            TimelineEventKind::CargoShip { .. }
+            | TimelineEventKind::PatrolHelicopter { .. }
+            | TimelineEventKind::Chinook { .. }
+            | TimelineEventKind::Explosion { .. } => TimelineCategory::Events,
+            TimelineEventKind::Alarm { .. } | TimelineEventKind::Geofence { .. } => {
+                TimelineCategory::Alarms
+            }
+            TimelineEventKind::ChatMention { .. } => TimelineCategory::Chat,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            TimelineEventKind::TeammateDied { name, .. } => format!("{} died", name),
+            TimelineEventKind::TeammateSpawned { name, .. } => format!("{} respawned", name),
+            TimelineEventKind::TeammateOnline { name, .. } => format!("{} came online", name),
+            TimelineEventKind::TeammateOffline { name, .. } => format!("{} went offline", name),
+            TimelineEventKind::CargoShip { .. } => "Cargo ship spawned".to_string(),
+            TimelineEventKind::PatrolHelicopter { .. } => "Patrol helicopter spawned".to_string(),
+            TimelineEventKind::Chinook { .. } => "Chinook spawned".to_string(),
+            TimelineEventKind::Explosion { .. } => "Explosion".to_string(),
+            TimelineEventKind::Alarm { title, .. } => title.clone(),
+            TimelineEventKind::Geofence {
+                zone,
+                subject,
+                entered,
+            } => format!(
+                "{} {} {}",
+                subject,
+                if *entered { "entered" } else { "left" },
+                zone
+            ),
+            TimelineEventKind::ChatMention { name, .. } => format!("{} mentioned you", name),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimelineEvent {
+    pub timestamp: i64,
+    pub kind: TimelineEventKind,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+    pub grid: Option<String>,
+    /// Position relative to the closest monument, see [`crate::utils::describe_location`].
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+impl TimelineEvent {
+    pub fn new(kind: TimelineEventKind) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            kind,
+            x: None,
+            y: None,
+            grid: None,
+            location: None,
+        }
+    }
+
+    /// Attaches a world position and its grid reference to the event.
+    pub fn at(mut self, x: f32, y: f32, map_size: Option<u32>) -> Self {
+        self.x = Some(x);
+        self.y = Some(y);
+        self.grid =
+            map_size.map(|map_size| crate::utils::precise_grid_reference(x, y, map_size as f32));
+        self
+    }
+
+    /// Describes the event position relative to the closest monument, call after [`Self::at`].
+    pub fn near(mut self, monuments: &[Monument]) -> Self {
+        self.location = self
+            .x
+            .zip(self.y)
+            .and_then(|(x, y)| crate::utils::describe_location(x, y, monuments));
+        self
+    }
+
+    /// Grid reference followed by the monument description when there is one.
+    pub fn place(&self) -> Option<String> {
+        match (&self.grid, &self.location) {
+            (Some(grid), Some(location)) => Some(format!("{}, {}", grid, location)),
+            (Some(grid), None) => Some(grid.clone()),
+            (None, location) => location.clone(),
+        }
+    }
+}
+
+fn timeline_filename(server_id: &str, wipe_time: u32) -> String {
+    format!("{}_{}.jsonl", server_id, wipe_time)
+}
+
+pub fn load_timeline(
+    server_id: &str,
+    wipe_time: u32,
+) -> Result<Vec<TimelineEvent>, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let timeline_dir = config_dir.join(APP_DIR_NAME).join(TIMELINE_DIR_NAME);
+    let filename = timeline_filename(server_id, wipe_time);
+
+    if !timeline_dir.exists() {
+        std::fs::create_dir_all(&timeline_dir)?;
+    }
+
+    let file = match std::fs::File::open(timeline_dir.join(&filename)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_timeline] No {filename} found...");
+            return Ok(vec![]);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut events = Vec::new();
+    for line in BufReader::new(file).lines() {
+        match serde_json::from_str::<TimelineEvent>(&line?) {
+            Ok(event) => events.push(event),
+            Err(e) => println!("[load_timeline] Skipping bad line in {filename}: {:?}", e),
+        }
+    }
+
+    Ok(events)
+}
+
+/// Appends a single event as one JSON line instead of rewriting the whole timeline.
+pub fn append_timeline(
+    server_id: &str,
+    wipe_time: u32,
+    event: &TimelineEvent,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let timeline_dir = config_dir.join(APP_DIR_NAME).join(TIMELINE_DIR_NAME);
+
+    if !timeline_dir.exists() {
+        std::fs::create_dir_all(&timeline_dir)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(timeline_dir.join(timeline_filename(server_id, wipe_time)))?;
+    file.write_all(format!("{}\n", serde_json::to_string(event)?).as_bytes())?;
+
+    Ok(())
+}
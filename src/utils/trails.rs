@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{bearing, distance};
+
+/// One hour of positions at the default poll interval.
+pub const TRAIL_CAPACITY: usize = 3600;
+/// Smaller moves are polling jitter or standing still and don't get a point.
+pub const TRAIL_MIN_DISTANCE: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailPoint {
+    pub timestamp: i64,
+    pub x: f32,
+    pub y: f32,
+    /// Last point of a segment, the member died or went offline here.
+    #[serde(default)]
+    pub end: bool,
+}
+
+impl TrailPoint {
+    pub fn new(timestamp: i64, x: f32, y: f32) -> Self {
+        Self {
+            timestamp,
+            x,
+            y,
+            end: false,
+        }
+    }
+}
+
+/// What a poll changes on a member's trail.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrailStep {
+    Push,
+    /// Closes the current segment so the respawn doesn't draw a line across the map.
+    End,
+}
+
+/// `active` is false while the member is dead or offline. `None` leaves the trail as it is.
+pub fn trail_step(
+    trail: Option<&VecDeque<TrailPoint>>,
+    x: f32,
+    y: f32,
+    active: bool,
+) -> Option<TrailStep> {
+    let last = trail.and_then(|trail| trail.back());
+    match last {
+        Some(last) if !active => (!last.end).then_some(TrailStep::End),
+        None if !active => None,
+        Some(last) if !last.end && distance((last.x, last.y), (x, y)) < TRAIL_MIN_DISTANCE => None,
+        _ => Some(TrailStep::Push),
+    }
+}
+
+pub fn apply_trail_step(trail: &mut VecDeque<TrailPoint>, step: TrailStep, point: TrailPoint) {
+    match step {
+        TrailStep::Push => push_trail_point(trail, point),
+        TrailStep::End => {
+            if let Some(last) = trail.back_mut() {
+                last.end = true;
+            }
+        }
+    }
+}
+
+pub fn push_trail_point(trail: &mut VecDeque<TrailPoint>, point: TrailPoint) {
+    if trail.len() >= TRAIL_CAPACITY {
+        trail.pop_front();
+    }
+    trail.push_back(point);
+}
+
+/// Distance a player has to cover before their direction of travel is trusted, so standing
+/// still or small position jitter doesn't spin a heading-up map around.
+pub const HEADING_MIN_DISTANCE: f32 = 8.0;
+/// How far back positions are used for the heading, older movement no longer says much.
+pub const HEADING_WINDOW_SECS: i64 = 30;
+
+/// Direction of travel as a compass bearing, from the latest position back to the most
+/// recent one at least `HEADING_MIN_DISTANCE` away. Rust+ reports no facing, so this is the
+/// closest there is. `None` while the player hasn't moved far enough.
+pub fn travel_heading(trail: &VecDeque<TrailPoint>) -> Option<f32> {
+    let latest = trail.back()?;
+    if latest.end {
+        return None;
+    }
+    trail
+        .iter()
+        .rev()
+        .skip(1)
+        .take_while(|point| !point.end && latest.timestamp - point.timestamp <= HEADING_WINDOW_SECS)
+        .find(|point| distance((point.x, point.y), (latest.x, latest.y)) >= HEADING_MIN_DISTANCE)
+        .map(|point| bearing((point.x, point.y), (latest.x, latest.y)))
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use crate::utils::{
+    ANNOTATIONS_DIR_NAME, APP_DIR_NAME, GEOFENCES_DIR_NAME, HISTORY_DIR_NAME, TIMELINE_DIR_NAME,
+};
+
+const WIPES_FILENAME: &str = "wipes.json";
+const ARCHIVE_DIR_NAME: &str = "archive";
+
+/// Directories holding files named `{server_id}_{wipe_time}.*`, which get archived once the server wipes.
+pub const WIPE_SCOPED_DIRS: &[&str] = &[
+    TIMELINE_DIR_NAME,
+    HISTORY_DIR_NAME,
+    GEOFENCES_DIR_NAME,
+    ANNOTATIONS_DIR_NAME,
+];
+
+pub fn load_wipe_times() -> Result<HashMap<String, u32>, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let config_path = config_dir.join(APP_DIR_NAME).join(WIPES_FILENAME);
+
+    if !config_path.exists() {
+        std::fs::create_dir_all(config_dir.join(APP_DIR_NAME))?;
+    }
+
+    let data = match std::fs::read_to_string(&config_path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("[load_wipe_times] No {WIPES_FILENAME} found...");
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }?;
+
+    let wipe_times = match data {
+        Some(content) => match serde_json::from_str::<HashMap<String, u32>>(&content) {
+            Ok(wipe_times) => wipe_times,
+            Err(e) => {
+                println!("[load_wipe_times] Failed to parse {WIPES_FILENAME}: {:?}", e);
+                HashMap::new()
+            }
+        },
+        None => HashMap::new(),
+    };
+
+    Ok(wipe_times)
+}
+
+pub fn save_wipe_times(wipe_times: &HashMap<String, u32>) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let config_path = config_dir.join(APP_DIR_NAME).join(WIPES_FILENAME);
+
+    if !config_path.exists() {
+        std::fs::create_dir_all(config_dir.join(APP_DIR_NAME))?;
+    }
+
+    std::fs::write(config_path, serde_json::to_string_pretty(wipe_times)?)?;
+
+    Ok(())
+}
+
+/// Remembers `wipe_time` for the server and returns the previously seen one if the server has wiped since.
+pub fn check_wipe(server_id: &str, wipe_time: u32) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+    let mut wipe_times = load_wipe_times()?;
+
+    let previous = wipe_times.insert(server_id.to_string(), wipe_time);
+    if previous != Some(wipe_time) {
+        save_wipe_times(&wipe_times)?;
+    }
+
+    Ok(previous.filter(|previous| *previous != wipe_time))
+}
+
+/// Moves every file belonging to the given wipe into `archive/`, keeping the directory layout.
+pub fn archive_wipe(server_id: &str, wipe_time: u32) -> Result<usize, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir().unwrap();
+    let app_dir = config_dir.join(APP_DIR_NAME);
+    let prefix = format!("{}_{}.", server_id, wipe_time);
+
+    let mut archived = 0;
+    for dir_name in WIPE_SCOPED_DIRS {
+        let dir = app_dir.join(dir_name);
+        if !dir.exists() {
+            continue;
+        }
+
+        let archive_dir = app_dir.join(ARCHIVE_DIR_NAME).join(dir_name);
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            if !file_name.to_string_lossy().starts_with(&prefix) {
+                continue;
+            }
+
+            if !archive_dir.exists() {
+                std::fs::create_dir_all(&archive_dir)?;
+            }
+            std::fs::rename(entry.path(), archive_dir.join(&file_name))?;
+            archived += 1;
+        }
+    }
+
+    Ok(archived)
+}